@@ -0,0 +1,201 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+    ops::Range,
+};
+
+use crate::flow::{branch_effects, follows};
+use crate::instruction::ConditionalOperation;
+use crate::packet::ExecutePacket;
+
+/// Way control flows from a basic block to another.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EdgeKind {
+    /// Branch taken to its target.
+    Taken,
+    /// Execution continuing with the following execute packet.
+    Fallthrough,
+    /// Call to a function, which returns to the fallthrough block: a ``CALLP``,
+    /// or a branch whose delay slots set up the return address in ``B3``.
+    Call,
+}
+
+impl Display for EdgeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Taken => write!(f, "taken"),
+            Self::Fallthrough => write!(f, "fallthrough"),
+            Self::Call => write!(f, "call"),
+        }
+    }
+}
+
+/// Transfer of control to a basic block.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Edge {
+    /// Address of the basic block control flows to.
+    pub target: u32,
+    pub kind: EdgeKind,
+    /// Condition the branch is predicated on, if any.
+    pub condition: Option<ConditionalOperation>,
+}
+
+/// Execute packets executed one after the other, ending with the execute
+/// packet in which a branch takes effect.
+#[derive(Clone, Debug)]
+pub struct BasicBlock {
+    /// Address of the first execute packet.
+    pub address: u32,
+    /// Indices of the execute packets of the block, in the execute packets
+    /// the graph is built from.
+    pub packets: Range<usize>,
+    pub successors: Vec<Edge>,
+}
+
+/// Control-flow graph of a sequence of execute packets.
+#[derive(Clone, Debug, Default)]
+pub struct ControlFlowGraph {
+    /// Basic blocks, by address.
+    pub blocks: BTreeMap<u32, BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    /// Builds the graph of the execute packets, in address order.
+    ///
+    /// Basic blocks start at branch targets and after the execute packets in
+    /// which branches take effect, once their delay slots are over.
+    /// Branches to an address held in a register have no edge, as their
+    /// target is unknown.
+    pub fn new(packets: &[ExecutePacket]) -> Self {
+        let effects = branch_effects(packets);
+        let indices: HashMap<u32, usize> = packets
+            .iter()
+            .enumerate()
+            .map(|(index, packet)| (packet.address, index))
+            .collect();
+
+        // Indices of the execute packets starting a basic block
+        let mut leaders = BTreeSet::new();
+        for (index, packet) in packets.iter().enumerate() {
+            if index == 0 || !effects[index - 1].is_empty() || !follows(&packets[index - 1], packet)
+            {
+                leaders.insert(index);
+            }
+            for branch in &effects[index] {
+                if let Some(target) = branch.target.and_then(|target| indices.get(&target)) {
+                    leaders.insert(*target);
+                }
+            }
+        }
+
+        let leaders: Vec<usize> = leaders.into_iter().collect();
+        let blocks = leaders
+            .iter()
+            .enumerate()
+            .map(|(position, &start)| {
+                let end = leaders.get(position + 1).copied().unwrap_or(packets.len());
+                let last = end - 1;
+                let mut successors = Vec::new();
+                let mut falls_through = true;
+                for branch in &effects[last] {
+                    if let Some(target) = branch.target {
+                        successors.push(Edge {
+                            target,
+                            kind: if branch.call {
+                                EdgeKind::Call
+                            } else {
+                                EdgeKind::Taken
+                            },
+                            condition: branch.condition,
+                        });
+                    }
+                    falls_through &= branch.falls_through();
+                }
+                if falls_through && end < packets.len() && follows(&packets[last], &packets[end]) {
+                    successors.push(Edge {
+                        target: packets[end].address,
+                        kind: EdgeKind::Fallthrough,
+                        condition: None,
+                    });
+                }
+                let address = packets[start].address;
+                (
+                    address,
+                    BasicBlock {
+                        address,
+                        packets: start..end,
+                        successors,
+                    },
+                )
+            })
+            .collect();
+        Self { blocks }
+    }
+
+    /// Returns the addresses of the basic blocks starting a function: the
+    /// targets of calls and the basic blocks without predecessors.
+    pub fn function_entries(&self) -> BTreeSet<u32> {
+        let mut targets = BTreeSet::new();
+        let mut calls = BTreeSet::new();
+        for edge in self.blocks.values().flat_map(|block| &block.successors) {
+            if edge.kind == EdgeKind::Call {
+                calls.insert(edge.target);
+            } else {
+                targets.insert(edge.target);
+            }
+        }
+        self.blocks
+            .keys()
+            .copied()
+            .filter(|address| calls.contains(address) || !targets.contains(address))
+            .collect()
+    }
+
+    /// Returns the addresses of the basic blocks flowing to the basic block.
+    pub fn predecessors(&self, address: u32) -> Vec<u32> {
+        self.blocks
+            .values()
+            .filter(|block| block.successors.iter().any(|edge| edge.target == address))
+            .map(|block| block.address)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Disassembler, instruction::register::Register, packet::execute_packets};
+
+    #[test]
+    fn predicated_branches_have_two_successors() {
+        // [A0] B.S1 0x1040, NOP 5, NOP, B.S2 B3, NOP 5, then NOPs
+        let mut words: Vec<u32> = vec![0xC0000810, 0x00008000, 0, 0x000C0362, 0x00008000];
+        words.resize(24, 0);
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let packets: Vec<ExecutePacket> =
+            execute_packets(Disassembler::from_slice(&data, 0x1000).map(|item| item.unwrap()))
+                .collect();
+
+        let graph = ControlFlowGraph::new(&packets);
+        let addresses: Vec<u32> = graph.blocks.keys().copied().collect();
+        assert_eq!(addresses, vec![0x1000, 0x1008, 0x1014, 0x1040]);
+        assert_eq!(
+            graph.blocks[&0x1000].successors,
+            vec![
+                Edge {
+                    target: 0x1040,
+                    kind: EdgeKind::Taken,
+                    condition: Some(ConditionalOperation::NonZero(Register::A(0))),
+                },
+                Edge {
+                    target: 0x1008,
+                    kind: EdgeKind::Fallthrough,
+                    condition: None,
+                },
+            ]
+        );
+        assert!(graph.blocks[&0x1008].successors.is_empty());
+        assert_eq!(graph.predecessors(0x1040), vec![0x1000, 0x1014]);
+        assert_eq!(graph.function_entries(), BTreeSet::from([0x1000, 0x1014]));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::PACKET_SIZE;
+use crate::instruction::{C6000Instruction, mnemonic::Mnemonic};
+use crate::symbols::Symbolizer;
+
+/// Columns of the CSV and TSV formats.
+pub const CSV_COLUMNS: [&str; 10] = [
+    "address",
+    "size",
+    "opcode",
+    "parallel",
+    "predicate",
+    "mnemonic",
+    "unit",
+    "operands",
+    "fetch_packet",
+    "execute_packet",
+];
+
+/// Quotes the field of a CSV or TSV row if it holds the separator.
+fn csv_field(text: &str, separator: char) -> String {
+    if text.contains([separator, '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        String::from(text)
+    }
+}
+
+/// Counter of the fetch packets and execute packets of the rows.
+#[derive(Default)]
+pub struct PacketIndices {
+    /// Fetch packet address and index of the last row.
+    fetch_packet: Option<(u32, usize)>,
+    /// Execute packet index and p-bit of the last instruction row.
+    execute_packet: Option<(usize, bool)>,
+}
+
+impl PacketIndices {
+    /// Returns the indices of the fetch packet and execute packet of the
+    /// next row, counted from the first row.
+    ///
+    /// Execute packets are delimited by the p-bits of the instructions,
+    /// and compact instruction headers are not part of any.
+    pub fn next(
+        &mut self,
+        address: u32,
+        instruction: &dyn C6000Instruction,
+    ) -> (usize, Option<usize>) {
+        let fetch_packet_address = address - address % PACKET_SIZE as u32;
+        let fetch_packet = match self.fetch_packet {
+            Some((previous, index)) if previous == fetch_packet_address => index,
+            Some((_, index)) => index + 1,
+            None => 0,
+        };
+        self.fetch_packet = Some((fetch_packet_address, fetch_packet));
+        if instruction.mnemonic() == Mnemonic::FPHEAD {
+            return (fetch_packet, None);
+        }
+        let execute_packet = match self.execute_packet {
+            Some((index, true)) => index,
+            Some((index, false)) => index + 1,
+            None => 0,
+        };
+        self.execute_packet = Some((execute_packet, instruction.get_p_bit()));
+        (fetch_packet, Some(execute_packet))
+    }
+}
+
+/// Formats a CSV or TSV row of the instruction, along with the indices of
+/// its fetch packet and execute packet in the output.
+pub fn format_row(
+    instruction: &dyn C6000Instruction,
+    address: u32,
+    (fetch_packet, execute_packet): (usize, Option<usize>),
+    symbolizer: &Symbolizer,
+    separator: char,
+) -> String {
+    let operands: Vec<String> = instruction
+        .operand_list()
+        .iter()
+        .map(|operand| symbolizer.operand(address, operand))
+        .collect();
+    let fields = [
+        format!("0x{address:08X}"),
+        instruction.size().to_string(),
+        if instruction.is_compact() {
+            format!("{:04X}", instruction.opcode())
+        } else {
+            format!("{:08X}", instruction.opcode())
+        },
+        instruction.is_parallel().to_string(),
+        instruction
+            .conditional_operation()
+            .filter(|operation| operation.register().is_some())
+            .map(|operation| operation.to_string())
+            .unwrap_or_default(),
+        instruction.mnemonic().to_string(),
+        instruction
+            .unit()
+            .map(|unit| unit.to_sided_string(instruction.side().unwrap_or_default()))
+            .unwrap_or_default(),
+        operands.join(", "),
+        fetch_packet.to_string(),
+        execute_packet
+            .map(|index| index.to_string())
+            .unwrap_or_default(),
+    ];
+    fields
+        .iter()
+        .map(|field| csv_field(field, separator))
+        .collect::<Vec<String>>()
+        .join(&separator.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Disassembler, symbols::SymbolTable};
+
+    #[test]
+    fn fields_holding_the_separator_are_quoted() {
+        assert_eq!(csv_field("B3", ','), "B3");
+        assert_eq!(csv_field("0x1234, B3", ','), "\"0x1234, B3\"");
+        assert_eq!(csv_field("0x1234, B3", '\t'), "0x1234, B3");
+        assert_eq!(csv_field("a \"b\"", '\t'), "\"a \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn rows_of_parallel_instructions_share_execute_packet() {
+        // MVK.S1 0x1234, A3 with its p-bit set, MVK.S2 0x1234, B3, then NOP
+        let words: [u32; 3] = [0x01891A29, 0x01891A2A, 0];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let instructions: Vec<_> = Disassembler::from_slice(&data, 0x1000)
+            .map(|item| item.unwrap())
+            .collect();
+        let symbols = SymbolTable::new();
+        let symbolizer = Symbolizer::new(&symbols, &instructions);
+        let mut indices = PacketIndices::default();
+        let rows: Vec<String> = instructions
+            .iter()
+            .map(|(address, instruction)| {
+                let packet = indices.next(*address, instruction.as_ref());
+                format_row(instruction.as_ref(), *address, packet, &symbolizer, '\t')
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "0x00001000\t4\t01891A29\tfalse\t\tMVK\tS1\t0x1234, A3\t0\t0",
+                "0x00001004\t4\t01891A2A\ttrue\t\tMVK\tS2\t0x1234, B3\t0\t0",
+                "0x00001008\t4\t00000000\tfalse\t\tNOP\t\t\t0\t1",
+            ]
+        );
+    }
+}
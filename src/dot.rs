@@ -0,0 +1,100 @@
+use crate::cfg::ControlFlowGraph;
+use crate::format_instruction;
+use crate::functions::find_functions;
+use crate::packet::ExecutePacket;
+use crate::symbols::{SymbolTable, Symbolizer};
+
+/// Escapes the text for a double-quoted Graphviz string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Returns a Graphviz digraph for every function of the graph, with a node
+/// holding the execute packets of every basic block.
+///
+/// Nodes are named after the address of their basic block, and only the
+/// edges between the basic blocks of a function are kept.
+pub fn digraphs(
+    graph: &ControlFlowGraph,
+    packets: &[ExecutePacket],
+    symbols: &SymbolTable,
+) -> String {
+    let symbolizer = Symbolizer::new(
+        symbols,
+        packets.iter().flat_map(|packet| &packet.instructions),
+    );
+    let name = |address: u32| {
+        symbols
+            .get(address)
+            .map_or_else(|| format!("0x{address:08X}"), String::from)
+    };
+    let mut text = String::new();
+    for function in find_functions(graph, packets).values() {
+        let mut lines = vec![
+            format!("digraph \"{}\" {{", escape(&name(function.address))),
+            String::from("    node [shape=box, fontname=\"monospace\"];"),
+        ];
+        let blocks = &function.blocks;
+        for block in blocks.iter().map(|address| &graph.blocks[address]) {
+            let mut label = format!("{}:\\l", escape(&name(block.address)));
+            for packet in &packets[block.packets.clone()] {
+                for (address, instruction) in &packet.instructions {
+                    let operands = symbolizer.operands(*address, instruction.as_ref());
+                    let line = format_instruction(instruction.as_ref(), *address, operands);
+                    label += &format!("{}\\l", escape(line.trim_end()));
+                }
+            }
+            lines.push(format!(
+                "    \"0x{:08X}\" [label=\"{label}\"];",
+                block.address
+            ));
+            for edge in &block.successors {
+                if !blocks.contains(&edge.target) {
+                    continue;
+                }
+                let label = match edge.condition {
+                    Some(condition) => format!("{} if {condition}", edge.kind),
+                    None => edge.kind.to_string(),
+                };
+                lines.push(format!(
+                    "    \"0x{:08X}\" -> \"0x{:08X}\" [label=\"{label}\"];",
+                    block.address, edge.target
+                ));
+            }
+        }
+        lines.push(String::from("}\n"));
+        text += &lines.join("\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Disassembler, packet::execute_packets};
+
+    #[test]
+    fn functions_are_digraphs_of_their_blocks() {
+        // [A0] B.S1 0x1014, NOP 5, NOP, B.S2 B3, NOP 5, then a NOP at 0x1014
+        let words: [u32; 6] = [0xC0000290, 0x00008000, 0, 0x000C0362, 0x00008000, 0];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let packets: Vec<ExecutePacket> =
+            execute_packets(Disassembler::from_slice(&data, 0x1000).map(|item| item.unwrap()))
+                .collect();
+        let mut symbols = SymbolTable::new();
+        symbols.insert(0x1014, "say \"hi\\n\"");
+
+        let text = digraphs(&ControlFlowGraph::new(&packets), &packets, &symbols);
+        let expected = [
+            r#"digraph "0x00001000" {"#,
+            r#"    node [shape=box, fontname="monospace"];"#,
+            r#"    "0x00001000" [label="0x00001000:\l0x00001000: C0000290        [ A0]  B.S1         say \"hi\\n\"\l0x00001004: 8000                   NOP          5\l"];"#,
+            r#"    "0x00001000" -> "0x00001014" [label="taken if A0"];"#,
+            r#"    "0x00001000" -> "0x00001008" [label="fallthrough"];"#,
+            r#"    "0x00001008" [label="0x00001008:\l0x00001008: 0                      NOP\l0x0000100C: C0362                  B.S2         B3\l0x00001010: 8000                   NOP          5\l"];"#,
+            r#"    "0x00001014" [label="say \"hi\\n\":\l0x00001014: 0                      NOP\l"];"#,
+            "}\n",
+        ];
+        assert_eq!(text, expected.join("\n"));
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+/// Result of decoding an instruction or a fetch packet.
+pub type Result<T> = std::result::Result<T, DecodeError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// No instruction format matches the opcode.
+    UnknownOpcode { opcode: u32 },
+    /// The opcode is not part of the instruction format or family.
+    OpcodeMismatch { opcode: u32, reason: String },
+    /// The opcode matches the instruction format, but one of its fields
+    /// holds a reserved value.
+    ReservedField {
+        opcode: u32,
+        field: String,
+        value: u32,
+    },
+    /// The instruction cannot be decoded from an opcode of this size.
+    Unsupported { reason: &'static str },
+    /// A compact instruction was decoded without a compact instruction header.
+    MissingHeader { opcode: u32 },
+    /// The last word of the fetch packet at the address is not a
+    /// compact instruction header.
+    NotFetchPacket { address: u32 },
+    /// A compact instruction header was found at an address other than
+    /// the last word of a fetch packet.
+    UnexpectedHeader { address: u32 },
+    /// The address of the fetch packet (PCE1) is not known.
+    UnknownPce1,
+    /// The branch does not use a displacement.
+    NotDisplacement,
+    /// A variable required by the decoder wasn't parsed.
+    MissingVariable { name: String },
+    /// A parsed variable has a different type than the one requested.
+    VariableType { expected: &'static str },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownOpcode { opcode } => {
+                write!(f, "Opcode 0x{opcode:08X} matches no instruction format")
+            }
+            Self::OpcodeMismatch { opcode, reason } => {
+                write!(f, "Opcode 0x{opcode:08X} does not match: {reason}")
+            }
+            Self::ReservedField {
+                opcode,
+                field,
+                value,
+            } => write!(
+                f,
+                "Opcode 0x{opcode:08X} has reserved {field} value (got {value:b})"
+            ),
+            Self::Unsupported { reason } => write!(f, "{reason}"),
+            Self::MissingHeader { opcode } => write!(
+                f,
+                "Compact opcode 0x{opcode:04X} requires a compact instruction header"
+            ),
+            Self::NotFetchPacket { address } => write!(
+                f,
+                "Not a fetch packet at 0x{address:08X}, use read_instruction instead."
+            ),
+            Self::UnexpectedHeader { address } => write!(
+                f,
+                "Compact instruction header found in unusual place (0x{address:08X})"
+            ),
+            Self::UnknownPce1 => write!(f, "PCE1 = 0"),
+            Self::NotDisplacement => write!(f, "Not displacement"),
+            Self::MissingVariable { name } => write!(f, "Parsing error ({name} is missing)"),
+            Self::VariableType { expected } => write!(f, "Not a {expected} variable"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,170 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::INSTRUCTION_SIZE;
+use crate::instruction::register::{Register, RegisterFile};
+use crate::instruction::{
+    C6000Instruction, ConditionalOperation,
+    branching::BranchInstruction,
+    mnemonic::{InstructionClass, Mnemonic},
+};
+use crate::packet::ExecutePacket;
+
+/// Register holding the return address of calls.
+const RETURN_ADDRESS: RegisterFile = RegisterFile::GeneralPurpose(Register::B(3));
+
+/// Transfer of control by a branch, taking effect after its delay slots.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Branch {
+    /// Address of the branch instruction.
+    pub address: u32,
+    /// Address branched to, unless it is held in a register.
+    pub target: Option<u32>,
+    /// Condition register the branch is predicated on, if any.
+    pub condition: Option<ConditionalOperation>,
+    /// Determines if the branch is a call, which returns to the execute
+    /// packet following the one it takes effect in: a ``CALLP``, or a branch
+    /// whose delay slots set up the return address in ``B3``.
+    pub call: bool,
+    /// Determines if the branch returns to the address held in ``B3``.
+    pub returns: bool,
+}
+
+impl Branch {
+    /// Returns the branch made by the instruction, if it is one.
+    pub fn new(address: u32, instruction: &dyn C6000Instruction) -> Option<Self> {
+        if instruction.class() != InstructionClass::Branch {
+            return None;
+        }
+        let target = BranchInstruction::target_of(instruction);
+        Some(Self {
+            address,
+            target,
+            condition: instruction
+                .conditional_operation()
+                .filter(|operation| operation.register().is_some()),
+            call: instruction.mnemonic() == Mnemonic::CALLP,
+            returns: target.is_none() && instruction.reads().contains(&RETURN_ADDRESS),
+        })
+    }
+
+    /// Determines if execution may continue with the execute packet
+    /// following the one the branch takes effect in.
+    pub fn falls_through(&self) -> bool {
+        self.condition.is_some() || self.call
+    }
+}
+
+/// Determines if the execute packet directly follows another one,
+/// possibly after the compact instruction header ending a fetch packet.
+pub fn follows(previous: &ExecutePacket, packet: &ExecutePacket) -> bool {
+    packet.address.wrapping_sub(previous.end()) <= INSTRUCTION_SIZE as u32
+}
+
+/// Returns the branches taking effect at the end of every execute packet,
+/// by index of the execute packet.
+///
+/// The delay slots of a branch are counted in cycles of the execute packets
+/// following it in address order, including multi-cycle ``NOP`` instructions
+/// and the ``NOP`` cycles of ``BNOP`` and ``CALLP``. Branches setting up
+/// ``B3`` during their delay slots are calls.
+pub fn branch_effects(packets: &[ExecutePacket]) -> Vec<Vec<Branch>> {
+    let delay_slots = Mnemonic::B.timing().delay_slots as u32;
+    let mut effects = vec![Vec::new(); packets.len()];
+    // Branches in flight, along with the cycles left until they take effect
+    let mut pending: Vec<(Branch, u32)> = Vec::new();
+    for (index, packet) in packets.iter().enumerate() {
+        if index > 0 && !follows(&packets[index - 1], packet) {
+            pending.clear();
+        }
+        let mut sets_return_address = false;
+        for (address, instruction) in &packet.instructions {
+            if let Some(branch) = Branch::new(*address, instruction.as_ref()) {
+                pending.push((branch, delay_slots + 1));
+            } else if instruction.writes().contains(&RETURN_ADDRESS) {
+                sets_return_address = true;
+            }
+        }
+        if sets_return_address {
+            for (branch, _) in pending.iter_mut().filter(|(branch, _)| !branch.returns) {
+                branch.call = true;
+            }
+        }
+        let cycles = packet.cycles();
+        pending.retain_mut(|(branch, cycles_left)| {
+            *cycles_left = cycles_left.saturating_sub(cycles);
+            if *cycles_left == 0 {
+                effects[index].push(*branch);
+            }
+            *cycles_left > 0
+        });
+    }
+    effects
+}
+
+/// Finds the instructions reachable from the entry points by recursive
+/// descent, following the branches once their delay slots are over.
+///
+/// Branches to an address held in a register end their path, as their
+/// target is unknown. Entry points which aren't the address of an execute
+/// packet are ignored.
+pub fn reachable(packets: &[ExecutePacket], entry_points: &[u32]) -> BTreeSet<u32> {
+    let effects = branch_effects(packets);
+    let indices: HashMap<u32, usize> = packets
+        .iter()
+        .enumerate()
+        .map(|(index, packet)| (packet.address, index))
+        .collect();
+    let mut visited = vec![false; packets.len()];
+    let mut worklist: Vec<usize> = entry_points
+        .iter()
+        .filter_map(|address| indices.get(address).copied())
+        .collect();
+    while let Some(mut index) = worklist.pop() {
+        while !visited[index] {
+            visited[index] = true;
+            let mut falls_through = true;
+            for branch in &effects[index] {
+                if let Some(target) = branch.target.and_then(|target| indices.get(&target)) {
+                    worklist.push(*target);
+                }
+                falls_through &= branch.falls_through();
+            }
+            if !falls_through
+                || index + 1 == packets.len()
+                || !follows(&packets[index], &packets[index + 1])
+            {
+                break;
+            }
+            index += 1;
+        }
+    }
+
+    packets
+        .iter()
+        .zip(visited)
+        .filter(|(_, visited)| *visited)
+        .flat_map(|(packet, _)| packet.instructions.iter().map(|(address, _)| *address))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Disassembler, packet::execute_packets};
+
+    #[test]
+    fn data_after_branches_is_not_reachable() {
+        // B.S1 0x1040, NOP 5, data, then NOP, B.S2 B3, NOP 5 at 0x1040
+        let data_word = 0x12345678;
+        let mut words: Vec<u32> = vec![0x00000810, 0x00008000];
+        words.resize(16, data_word);
+        words.extend([0, 0x000C0362, 0x00008000, data_word]);
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let packets: Vec<ExecutePacket> =
+            execute_packets(Disassembler::from_slice(&data, 0x1000).map(|item| item.unwrap()))
+                .collect();
+
+        let addresses: Vec<u32> = reachable(&packets, &[0x1000]).into_iter().collect();
+        assert_eq!(addresses, vec![0x1000, 0x1004, 0x1040, 0x1044, 0x1048]);
+    }
+}
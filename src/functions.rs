@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::cfg::{ControlFlowGraph, EdgeKind};
+use crate::flow::{Branch, follows};
+use crate::packet::ExecutePacket;
+
+/// Function inferred from a control-flow graph.
+#[derive(Clone, Debug)]
+pub struct Function {
+    /// Address of the first basic block.
+    pub address: u32,
+    /// Address following the last instruction of the function.
+    pub end: u32,
+    /// Number of bytes of the basic blocks of the function, leaving out
+    /// the gaps between them.
+    pub size: u32,
+    /// Addresses of the basic blocks of the function.
+    pub blocks: BTreeSet<u32>,
+    /// Addresses of the functions called, including tail calls.
+    pub callees: BTreeSet<u32>,
+    /// Determines if the function returns to the address held in ``B3``.
+    pub returns: bool,
+}
+
+/// Finds the functions of a graph built from the execute packets.
+///
+/// Functions start at the targets of calls (``CALLP``, or branches setting up
+/// ``B3`` in their delay slots) and at the basic blocks without predecessors
+/// starting a run of execute packets, such as the first one. Basic blocks
+/// without predecessors right after a block which doesn't fall through to
+/// them are unreachable, like data following a branch, so they don't start
+/// a function.
+///
+/// Functions are made of the basic blocks reachable from their start without
+/// calls, up to their returns (``B B3``) or the start of another function.
+pub fn find_functions(
+    graph: &ControlFlowGraph,
+    packets: &[ExecutePacket],
+) -> BTreeMap<u32, Function> {
+    let entries: BTreeSet<u32> = graph
+        .function_entries()
+        .into_iter()
+        .filter(|&entry| {
+            let start = graph.blocks[&entry].packets.start;
+            let unreachable = start > 0
+                && follows(&packets[start - 1], &packets[start])
+                && graph.predecessors(entry).is_empty();
+            !unreachable
+        })
+        .collect();
+    entries
+        .iter()
+        .map(|&entry| {
+            let mut function = Function {
+                address: entry,
+                end: entry,
+                size: 0,
+                blocks: BTreeSet::new(),
+                callees: BTreeSet::new(),
+                returns: false,
+            };
+            let mut worklist = vec![entry];
+            while let Some(address) = worklist.pop() {
+                let Some(block) = graph.blocks.get(&address) else {
+                    continue;
+                };
+                if !function.blocks.insert(address) {
+                    continue;
+                }
+                let block_packets = &packets[block.packets.clone()];
+                if let (Some(first), Some(last)) = (block_packets.first(), block_packets.last()) {
+                    function.end = function.end.max(last.end());
+                    function.size += last.end().wrapping_sub(first.address);
+                }
+                function.returns |= block_packets
+                    .iter()
+                    .flat_map(|packet| &packet.instructions)
+                    .filter_map(|(address, instruction)| {
+                        Branch::new(*address, instruction.as_ref())
+                    })
+                    .any(|branch| branch.returns);
+                for edge in &block.successors {
+                    let other_function = edge.target != entry && entries.contains(&edge.target);
+                    match edge.kind {
+                        EdgeKind::Call => {
+                            function.callees.insert(edge.target);
+                        }
+                        // Tail call
+                        EdgeKind::Taken if other_function => {
+                            function.callees.insert(edge.target);
+                        }
+                        _ if other_function => (),
+                        _ => worklist.push(edge.target),
+                    }
+                }
+            }
+            (entry, function)
+        })
+        .collect()
+}
+
+/// Returns the calls between the functions, as ``(caller, callee)`` pairs.
+pub fn call_graph(functions: &BTreeMap<u32, Function>) -> Vec<(u32, u32)> {
+    functions
+        .values()
+        .flat_map(|function| {
+            function
+                .callees
+                .iter()
+                .map(|callee| (function.address, *callee))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Disassembler, cfg::Edge, packet::execute_packets};
+
+    #[test]
+    fn branches_setting_up_b3_are_calls() {
+        // B.S1 0x1014, MVK.S2 0x1234, B3, NOP 4, B.S2 B3, NOP 5,
+        // then B.S2 B3, NOP 5 at 0x1014 and an unreachable NOP
+        let words: [u32; 8] = [
+            0x00000290, 0x01891A2A, 0x00006000, 0x000C0362, 0x00008000, 0x000C0362, 0x00008000, 0,
+        ];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let packets: Vec<ExecutePacket> =
+            execute_packets(Disassembler::from_slice(&data, 0x1000).map(|item| item.unwrap()))
+                .collect();
+
+        let functions = find_functions(&ControlFlowGraph::new(&packets), &packets);
+        let addresses: Vec<u32> = functions.keys().copied().collect();
+        assert_eq!(addresses, vec![0x1000, 0x1014]);
+        let main = &functions[&0x1000];
+        assert_eq!(main.blocks, BTreeSet::from([0x1000, 0x100C]));
+        assert_eq!(main.size, 0x14);
+        assert!(main.returns);
+        assert_eq!(functions[&0x1014].size, 8);
+        assert_eq!(call_graph(&functions), vec![(0x1000, 0x1014)]);
+    }
+
+    #[test]
+    fn addkpc_in_parallel_with_a_branch_makes_a_call() {
+        // B.S1 0x1014 || ADDKPC.S2 0x100C, B3, 4, NOP, then B.S2 B3, NOP 5
+        // at 0x100C and B.S2 B3, NOP 5 at 0x1014, then an unreachable NOP
+        let words: [u32; 8] = [
+            0x00000291, 0x01838162, 0, 0x000C0362, 0x00008000, 0x000C0362, 0x00008000, 0,
+        ];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let packets: Vec<ExecutePacket> =
+            execute_packets(Disassembler::from_slice(&data, 0x1000).map(|item| item.unwrap()))
+                .collect();
+
+        let graph = ControlFlowGraph::new(&packets);
+        assert!(graph.blocks[&0x1000].successors.contains(&Edge {
+            target: 0x1014,
+            kind: EdgeKind::Call,
+            condition: None,
+        }));
+        let functions = find_functions(&graph, &packets);
+        let addresses: Vec<u32> = functions.keys().copied().collect();
+        assert_eq!(addresses, vec![0x1000, 0x1014]);
+        assert_eq!(functions[&0x1000].blocks, BTreeSet::from([0x1000, 0x100C]));
+        assert_eq!(call_graph(&functions), vec![(0x1000, 0x1014)]);
+    }
+
+    #[test]
+    fn data_between_blocks_is_not_part_of_functions() {
+        // B.S1 0x1040, NOP 5, data, then NOP, B.S2 B3, NOP 5 at 0x1040
+        let data_word = 0x12345678;
+        let mut words: Vec<u32> = vec![0x00000810, 0x00008000];
+        words.resize(16, data_word);
+        words.extend([0, 0x000C0362, 0x00008000, data_word]);
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let packets: Vec<ExecutePacket> =
+            execute_packets(Disassembler::from_slice(&data, 0x1000).map(|item| item.unwrap()))
+                .collect();
+
+        let functions = find_functions(&ControlFlowGraph::new(&packets), &packets);
+        let addresses: Vec<u32> = functions.keys().copied().collect();
+        assert_eq!(addresses, vec![0x1000]);
+        let main = &functions[&0x1000];
+        assert_eq!(main.blocks, BTreeSet::from([0x1000, 0x1040]));
+        assert_eq!(main.end, 0x104C);
+        assert_eq!(main.size, 0x14);
+    }
+}
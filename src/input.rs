@@ -0,0 +1,146 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::Endianness;
+use crate::symbols::SymbolTable;
+
+pub mod boot_table;
+pub mod coff;
+pub mod elf;
+pub mod ihex;
+pub mod srec;
+
+/// Contiguous block of bytes loaded at an address of the target.
+pub struct Section {
+    pub name: String,
+    /// Address of the first byte of the section.
+    pub address: u32,
+    pub data: Vec<u8>,
+    /// Determines if the section holds code.
+    pub executable: bool,
+}
+
+/// Contents of an input file, as loaded in the memory of the target.
+pub struct Image {
+    pub sections: Vec<Section>,
+    /// Byte order declared by the file, if the format declares it.
+    pub endianness: Option<Endianness>,
+    pub symbols: SymbolTable,
+    /// Address execution starts at, if the format declares it.
+    pub entry_point: Option<u32>,
+}
+
+impl Image {
+    /// Creates an image from a raw binary loaded at the address.
+    pub fn from_raw(data: Vec<u8>, address: u32) -> Self {
+        Self {
+            sections: vec![Section {
+                name: String::new(),
+                address,
+                data,
+                executable: true,
+            }],
+            endianness: None,
+            symbols: SymbolTable::new(),
+            entry_point: None,
+        }
+    }
+
+    /// Creates an image from blocks of bytes tagged with their address, such
+    /// as the records of a hex file.
+    ///
+    /// Adjacent blocks are merged into a single section, while gaps
+    /// between them start a new section.
+    pub fn from_blocks(mut blocks: Vec<(u32, Vec<u8>)>, entry_point: Option<u32>) -> Self {
+        blocks.sort_by_key(|(address, _)| *address);
+        let mut sections: Vec<Section> = Vec::new();
+        for (address, data) in blocks {
+            if let Some(section) = sections.last_mut() {
+                let offset = address.wrapping_sub(section.address) as usize;
+                if offset <= section.data.len() {
+                    // Later blocks overwrite the bytes they overlap
+                    let overlap = (section.data.len() - offset).min(data.len());
+                    section.data[offset..offset + overlap].copy_from_slice(&data[..overlap]);
+                    section.data.extend_from_slice(&data[overlap..]);
+                    continue;
+                }
+            }
+            sections.push(Section {
+                name: String::new(),
+                address,
+                data,
+                executable: true,
+            });
+        }
+        Self {
+            sections,
+            endianness: None,
+            symbols: SymbolTable::new(),
+            entry_point,
+        }
+    }
+
+    /// Returns the sections holding code.
+    pub fn executable_sections(&self) -> impl Iterator<Item = &Section> {
+        self.sections.iter().filter(|section| section.executable)
+    }
+}
+
+/// Bounds-checked reader of the fields of a file.
+pub(crate) struct FieldReader<'a> {
+    data: &'a [u8],
+    pub endianness: Endianness,
+}
+
+impl<'a> FieldReader<'a> {
+    pub fn new(data: &'a [u8], endianness: Endianness) -> Self {
+        Self { data, endianness }
+    }
+
+    pub fn bytes(&self, offset: usize, size: usize) -> Result<&'a [u8]> {
+        offset
+            .checked_add(size)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| invalid_data(format!("File truncated (reading 0x{offset:X})")))
+    }
+
+    pub fn u8(&self, offset: usize) -> Result<u8> {
+        Ok(self.bytes(offset, 1)?[0])
+    }
+
+    pub fn u16(&self, offset: usize) -> Result<u16> {
+        let bytes = self.bytes(offset, 2)?;
+        Ok(self.endianness.read_u16([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&self, offset: usize) -> Result<u32> {
+        let bytes = self.bytes(offset, 4)?;
+        Ok(self
+            .endianness
+            .read_u32([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a null-terminated string.
+    pub fn string(&self, offset: usize) -> Result<String> {
+        let bytes = self.data.get(offset..).unwrap_or_default();
+        let length = bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or_else(|| invalid_data(format!("Unterminated string at 0x{offset:X}")))?;
+        Ok(String::from_utf8_lossy(&bytes[..length]).into_owned())
+    }
+}
+
+pub(crate) fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Decodes a string of hexadecimal digit pairs.
+pub(crate) fn hex_bytes(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,59 @@
+use std::io::Result;
+
+use crate::Endianness;
+use crate::input::{FieldReader, Image};
+
+/// Parses a TI C6000 boot table, as generated by ``hex6x -boot``.
+///
+/// The table starts with the entry point, followed by section records made
+/// of the size in bytes, the destination address and the data (padded to a
+/// multiple of 4 bytes). A section size of zero ends the table.
+pub fn parse(data: &[u8], endianness: Endianness) -> Result<Image> {
+    let reader = FieldReader::new(data, endianness);
+    let entry_point = reader.u32(0)?;
+    let mut blocks = Vec::new();
+    let mut offset = 4;
+    loop {
+        let size = reader.u32(offset)? as usize;
+        if size == 0 {
+            break;
+        }
+        let address = reader.u32(offset + 4)?;
+        blocks.push((address, reader.bytes(offset + 8, size)?.to_vec()));
+        offset += 8 + size.next_multiple_of(4);
+    }
+    Ok(Image::from_blocks(blocks, Some(entry_point)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_are_loaded_at_their_destination() {
+        let mut data = Vec::new();
+        for word in [0x00800000u32, 6, 0x00800000] {
+            data.extend(word.to_be_bytes());
+        }
+        data.extend([1, 2, 3, 4, 5, 6, 0, 0]);
+        for word in [4u32, 0x00900000, 0x0A0B0C0D, 0] {
+            data.extend(word.to_be_bytes());
+        }
+
+        let image = parse(&data, Endianness::Big).unwrap();
+        assert_eq!(image.entry_point, Some(0x00800000));
+        assert_eq!(image.sections.len(), 2);
+        assert_eq!(image.sections[0].data, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(image.sections[1].address, 0x00900000);
+        assert_eq!(image.sections[1].data, vec![0x0A, 0x0B, 0x0C, 0x0D]);
+    }
+
+    #[test]
+    fn unterminated_table_is_rejected() {
+        let mut data = Vec::new();
+        for word in [0x00800000u32, 4, 0x00800000, 0] {
+            data.extend(word.to_le_bytes());
+        }
+        assert!(parse(&data, Endianness::Little).is_err());
+    }
+}
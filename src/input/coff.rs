@@ -0,0 +1,212 @@
+use std::io::Result;
+
+use crate::Endianness;
+use crate::input::{FieldReader, Image, Section, invalid_data};
+use crate::symbols::SymbolTable;
+
+const VERSION_COFF1: u16 = 0x00C1;
+const VERSION_COFF2: u16 = 0x00C2;
+/// Target ID of the TMS320C6000 family.
+const TARGET_C6000: u16 = 0x0099;
+
+const FILE_HEADER_SIZE: usize = 22;
+const OPTIONAL_HEADER_SIZE: usize = 28;
+const COFF1_SECTION_HEADER_SIZE: usize = 40;
+const COFF2_SECTION_HEADER_SIZE: usize = 48;
+const SYMBOL_ENTRY_SIZE: usize = 18;
+
+const FLAG_DSECT: u32 = 0x01;
+const FLAG_NOLOAD: u32 = 0x02;
+const FLAG_COPY: u32 = 0x10;
+const FLAG_TEXT: u32 = 0x20;
+const FLAG_BSS: u32 = 0x80;
+
+const CLASS_EXTERNAL: u8 = 2;
+const CLASS_STATIC: u8 = 3;
+const CLASS_LABEL: u8 = 6;
+
+/// Returns the byte order of the file if it is a TI C6000 COFF1 or COFF2 file.
+fn detect_endianness(data: &[u8]) -> Option<Endianness> {
+    [Endianness::Little, Endianness::Big]
+        .into_iter()
+        .find(|endianness| {
+            let reader = FieldReader::new(data, *endianness);
+            matches!(reader.u16(0), Ok(VERSION_COFF1 | VERSION_COFF2))
+                && reader.u16(20).is_ok_and(|target| target == TARGET_C6000)
+        })
+}
+
+/// Determines if the data starts with a TI C6000 COFF1 or COFF2 file header.
+pub fn is_coff(data: &[u8]) -> bool {
+    detect_endianness(data).is_some()
+}
+
+/// Parses a TI C6000 COFF1 or COFF2 object file or executable, loading the
+/// initialized sections at their run addresses.
+pub fn parse(data: &[u8]) -> Result<Image> {
+    let Some(endianness) = detect_endianness(data) else {
+        return Err(invalid_data(String::from(
+            "Not a TI C6000 COFF1 or COFF2 file",
+        )));
+    };
+    let reader = FieldReader::new(data, endianness);
+    let coff2 = reader.u16(0)? == VERSION_COFF2;
+    let section_count = reader.u16(2)? as usize;
+    let symbol_offset = reader.u32(8)? as usize;
+    let symbol_count = reader.u32(12)? as usize;
+    let optional_header_size = reader.u16(16)? as usize;
+    let strings_offset = symbol_offset + symbol_count * SYMBOL_ENTRY_SIZE;
+
+    let section_header_size = if coff2 {
+        COFF2_SECTION_HEADER_SIZE
+    } else {
+        COFF1_SECTION_HEADER_SIZE
+    };
+    let first_header = FILE_HEADER_SIZE + optional_header_size;
+
+    let mut sections = Vec::new();
+    for index in 0..section_count {
+        let header = first_header + index * section_header_size;
+        let flags = if coff2 {
+            reader.u32(header + 40)?
+        } else {
+            reader.u16(header + 36)? as u32
+        };
+        let size = reader.u32(header + 16)? as usize;
+        let data_offset = reader.u32(header + 20)? as usize;
+        if flags & (FLAG_DSECT | FLAG_NOLOAD | FLAG_COPY | FLAG_BSS) != 0
+            || size == 0
+            || data_offset == 0
+        {
+            continue;
+        }
+        sections.push(Section {
+            name: read_name(&reader, header, strings_offset)?,
+            address: reader.u32(header + 8)?,
+            data: reader.bytes(data_offset, size)?.to_vec(),
+            executable: flags & FLAG_TEXT != 0,
+        });
+    }
+
+    Ok(Image {
+        sections,
+        endianness: Some(endianness),
+        symbols: read_symbols(&reader, symbol_offset, symbol_count, strings_offset)?,
+        entry_point: if optional_header_size >= OPTIONAL_HEADER_SIZE {
+            Some(reader.u32(FILE_HEADER_SIZE + 16)?)
+        } else {
+            None
+        },
+    })
+}
+
+/// Reads the external, static and label symbols defined in a section.
+fn read_symbols(
+    reader: &FieldReader,
+    offset: usize,
+    count: usize,
+    strings_offset: usize,
+) -> Result<SymbolTable> {
+    let mut symbols = SymbolTable::new();
+    let mut index = 0;
+    while index < count {
+        let entry = offset + index * SYMBOL_ENTRY_SIZE;
+        let section_number = reader.u16(entry + 12)? as i16;
+        let storage_class = reader.u8(entry + 16)?;
+        // Auxiliary entries follow the symbol
+        index += 1 + reader.u8(entry + 17)? as usize;
+        if section_number <= 0
+            || !matches!(storage_class, CLASS_EXTERNAL | CLASS_STATIC | CLASS_LABEL)
+        {
+            continue;
+        }
+        let name = read_name(reader, entry, strings_offset)?;
+        // Section symbols (``.text``...) and internal symbols aren't names
+        if name.is_empty() || name.starts_with('.') || name.starts_with('$') {
+            continue;
+        }
+        symbols.insert(reader.u32(entry + 8)?, &name);
+    }
+    Ok(symbols)
+}
+
+/// Reads an 8-byte name field, which holds either the name itself
+/// or an offset into the string table.
+fn read_name(reader: &FieldReader, offset: usize, strings_offset: usize) -> Result<String> {
+    if reader.u32(offset)? == 0 {
+        return reader.string(strings_offset + reader.u32(offset + 4)? as usize);
+    }
+    let bytes = reader.bytes(offset, 8)?;
+    let length = bytes.iter().position(|byte| *byte == 0).unwrap_or(8);
+    Ok(String::from_utf8_lossy(&bytes[..length]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a little-endian COFF2 file with a ``.text`` section at 0x400
+    /// and a ``.bss`` section, along with a symbol table holding the
+    /// ``.text`` section symbol and ``_main`` at 0x404.
+    fn coff2(text: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for field in [VERSION_COFF2, 2] {
+            data.extend(field.to_le_bytes());
+        }
+        for field in [0u32, 0, 0] {
+            data.extend(field.to_le_bytes());
+        }
+        for field in [0u16, 0x0100, TARGET_C6000] {
+            data.extend(field.to_le_bytes());
+        }
+
+        let text_offset = (FILE_HEADER_SIZE + 2 * COFF2_SECTION_HEADER_SIZE) as u32;
+        let mut section = |name: &[u8; 8], address: u32, size: u32, offset: u32, flags: u32| {
+            data.extend(name);
+            for field in [address, address, size, offset, 0, 0, 0, 0, flags, 0] {
+                data.extend(field.to_le_bytes());
+            }
+        };
+        section(
+            b".text\0\0\0",
+            0x400,
+            text.len() as u32,
+            text_offset,
+            FLAG_TEXT,
+        );
+        section(b".bss\0\0\0\0", 0x800, 0x100, 0, FLAG_BSS);
+        data.extend(text);
+
+        let symbol_offset = data.len() as u32;
+        data[8..12].copy_from_slice(&symbol_offset.to_le_bytes());
+        data[12..16].copy_from_slice(&3u32.to_le_bytes());
+        let mut symbol = |name: &[u8; 8], value: u32, class: u8, aux: u8| {
+            data.extend(name);
+            data.extend(value.to_le_bytes());
+            data.extend(1u16.to_le_bytes());
+            data.extend(0u16.to_le_bytes());
+            data.extend([class, aux]);
+        };
+        symbol(b".text\0\0\0", 0x400, CLASS_STATIC, 1);
+        symbol(&[0; 8], 0, 0, 0);
+        symbol(b"_main\0\0\0", 0x404, CLASS_EXTERNAL, 0);
+        data.extend(4u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn text_sections_are_loaded_at_their_run_address() {
+        let data = coff2(&[1, 2, 3, 4]);
+        assert!(is_coff(&data));
+        let image = parse(&data).unwrap();
+        assert_eq!(image.endianness, Some(Endianness::Little));
+        assert_eq!(image.sections.len(), 1);
+        let section = &image.sections[0];
+        assert_eq!(section.name, ".text");
+        assert_eq!(section.address, 0x400);
+        assert!(section.executable);
+        assert_eq!(section.data, vec![1, 2, 3, 4]);
+        let symbols: Vec<(u32, &str)> = image.symbols.iter().collect();
+        assert_eq!(symbols, vec![(0x404, "_main")]);
+    }
+}
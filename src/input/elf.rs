@@ -0,0 +1,217 @@
+use std::io::Result;
+
+use crate::Endianness;
+use crate::input::{FieldReader, Image, Section, invalid_data};
+use crate::symbols::SymbolTable;
+
+const MAGIC: &[u8; 4] = b"\x7FELF";
+const CLASS_32: u8 = 1;
+const DATA_LITTLE: u8 = 1;
+const DATA_BIG: u8 = 2;
+/// ``EM_TI_C6000``
+const MACHINE_C6000: u16 = 140;
+
+const SECTION_SYMTAB: u32 = 2;
+const SECTION_NOBITS: u32 = 8;
+const FLAG_ALLOC: u32 = 0x2;
+const FLAG_EXECINSTR: u32 = 0x4;
+
+const SYMBOL_ENTRY_SIZE: usize = 16;
+const SYMBOL_FUNC: u8 = 2;
+const SECTION_INDEX_RESERVED: u16 = 0xFF00;
+
+/// Determines if the data starts with the ELF magic number.
+pub fn is_elf(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Parses a 32-bit TI C6000 ELF object file or executable, loading the
+/// allocated sections at their addresses.
+pub fn parse(data: &[u8]) -> Result<Image> {
+    if !is_elf(data) {
+        return Err(invalid_data(String::from("Not an ELF file")));
+    }
+    let mut reader = FieldReader::new(data, Endianness::Little);
+    if reader.u8(4)? != CLASS_32 {
+        return Err(invalid_data(String::from(
+            "Only 32-bit ELF files are supported",
+        )));
+    }
+    reader.endianness = match reader.u8(5)? {
+        DATA_LITTLE => Endianness::Little,
+        DATA_BIG => Endianness::Big,
+        value => return Err(invalid_data(format!("Invalid ELF data encoding ({value})"))),
+    };
+    let machine = reader.u16(18)?;
+    if machine != MACHINE_C6000 {
+        return Err(invalid_data(format!(
+            "ELF file is not for the TI C6000 (machine {machine})"
+        )));
+    }
+
+    let section_offset = reader.u32(32)? as usize;
+    let section_entry_size = reader.u16(46)? as usize;
+    let section_count = reader.u16(48)? as usize;
+    let names_index = reader.u16(50)? as usize;
+    let header_offset = |index: usize| section_offset + index * section_entry_size;
+
+    let names_offset = if names_index < section_count {
+        Some(reader.u32(header_offset(names_index) + 16)? as usize)
+    } else {
+        None
+    };
+
+    let mut sections = Vec::new();
+    let mut symbols = SymbolTable::new();
+    for index in 0..section_count {
+        let header = header_offset(index);
+        let section_type = reader.u32(header + 4)?;
+        let flags = reader.u32(header + 8)?;
+        if section_type == SECTION_SYMTAB {
+            let strings_header = header_offset(reader.u32(header + 24)? as usize);
+            read_symbols(
+                &reader,
+                reader.u32(header + 16)? as usize,
+                reader.u32(header + 20)? as usize / SYMBOL_ENTRY_SIZE,
+                reader.u32(strings_header + 16)? as usize,
+                &mut symbols,
+            )?;
+        }
+        if flags & FLAG_ALLOC == 0 || section_type == SECTION_NOBITS {
+            continue;
+        }
+        let name = match names_offset {
+            Some(names_offset) => reader.string(names_offset + reader.u32(header)? as usize)?,
+            None => String::new(),
+        };
+        let address = reader.u32(header + 12)?;
+        let offset = reader.u32(header + 16)? as usize;
+        let size = reader.u32(header + 20)? as usize;
+        sections.push(Section {
+            name,
+            address,
+            data: reader.bytes(offset, size)?.to_vec(),
+            executable: flags & FLAG_EXECINSTR != 0,
+        });
+    }
+
+    Ok(Image {
+        sections,
+        endianness: Some(reader.endianness),
+        symbols,
+        entry_point: Some(reader.u32(24)?).filter(|entry| *entry != 0),
+    })
+}
+
+/// Reads the functions, objects and labels defined in a symbol table.
+fn read_symbols(
+    reader: &FieldReader,
+    offset: usize,
+    count: usize,
+    strings_offset: usize,
+    symbols: &mut SymbolTable,
+) -> Result<()> {
+    for index in 0..count {
+        let entry = offset + index * SYMBOL_ENTRY_SIZE;
+        // Only untyped symbols (labels), objects and functions are kept
+        let symbol_type = reader.u8(entry + 12)? & 0xF;
+        let section_index = reader.u16(entry + 14)?;
+        if symbol_type > SYMBOL_FUNC
+            || section_index == 0
+            || section_index >= SECTION_INDEX_RESERVED
+        {
+            continue;
+        }
+        let name = reader.string(strings_offset + reader.u32(entry)? as usize)?;
+        // Mapping symbols (``$C``, ``$d``...) mark code and data, they aren't names
+        if name.is_empty() || name.starts_with('$') {
+            continue;
+        }
+        symbols.insert(reader.u32(entry + 4)?, &name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a big-endian ELF file with a ``.text`` section at 0x8000,
+    /// and a symbol table holding ``_main`` at 0x8004 and a mapping symbol.
+    fn big_endian_elf(text: &[u8]) -> Vec<u8> {
+        let names = b"\0.text\0.shstrtab\0.symtab\0_main\0$C\0";
+        let mut data = vec![0u8; 52];
+        data[..6].copy_from_slice(&[0x7F, b'E', b'L', b'F', CLASS_32, DATA_BIG]);
+        data[18..20].copy_from_slice(&MACHINE_C6000.to_be_bytes());
+
+        let text_offset = data.len() as u32;
+        data.extend(text);
+        let names_offset = data.len() as u32;
+        data.extend(names);
+        let symbols_offset = data.len() as u32;
+        data.extend([0; SYMBOL_ENTRY_SIZE]);
+        for (name, value, info) in [(25u32, 0x8004u32, SYMBOL_FUNC), (31, 0x8000, 0)] {
+            data.extend(name.to_be_bytes());
+            data.extend(value.to_be_bytes());
+            data.extend(0u32.to_be_bytes());
+            data.extend([info, 0]);
+            data.extend(1u16.to_be_bytes());
+        }
+        let section_offset = data.len() as u32;
+        data[32..36].copy_from_slice(&section_offset.to_be_bytes());
+        data[46..48].copy_from_slice(&40u16.to_be_bytes());
+        data[48..50].copy_from_slice(&4u16.to_be_bytes());
+        data[50..52].copy_from_slice(&2u16.to_be_bytes());
+
+        let mut section = |fields: [u32; 7]| {
+            for field in fields.into_iter().chain([0, 4, 0]) {
+                data.extend(field.to_be_bytes());
+            }
+        };
+        section([0; 7]);
+        section([
+            1,
+            1,
+            FLAG_ALLOC | FLAG_EXECINSTR,
+            0x8000,
+            text_offset,
+            text.len() as u32,
+            0,
+        ]);
+        section([7, 3, 0, 0, names_offset, names.len() as u32, 0]);
+        section([
+            17,
+            SECTION_SYMTAB,
+            0,
+            0,
+            symbols_offset,
+            3 * SYMBOL_ENTRY_SIZE as u32,
+            2,
+        ]);
+        data
+    }
+
+    #[test]
+    fn executable_sections_are_loaded_at_their_address() {
+        let image = parse(&big_endian_elf(&[1, 2, 3, 4])).unwrap();
+        assert_eq!(image.endianness, Some(Endianness::Big));
+        let sections: Vec<&Section> = image.executable_sections().collect();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name, ".text");
+        assert_eq!(sections[0].address, 0x8000);
+        assert_eq!(sections[0].data, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn symbols_are_read_without_mapping_symbols() {
+        let image = parse(&big_endian_elf(&[0; 8])).unwrap();
+        let symbols: Vec<(u32, &str)> = image.symbols.iter().collect();
+        assert_eq!(symbols, vec![(0x8004, "_main")]);
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let data = big_endian_elf(&[]);
+        assert!(parse(&data[..60]).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::Result;
+
+use crate::input::{Image, hex_bytes, invalid_data};
+
+const RECORD_DATA: u8 = 0x00;
+const RECORD_END_OF_FILE: u8 = 0x01;
+const RECORD_EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const RECORD_START_SEGMENT_ADDRESS: u8 = 0x03;
+const RECORD_EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const RECORD_START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// Determines if the data looks like an Intel HEX file.
+pub fn is_ihex(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(b":")
+}
+
+/// Parses an Intel HEX file, loading the data records at their addresses.
+pub fn parse(data: &[u8]) -> Result<Image> {
+    let text = str::from_utf8(data)
+        .map_err(|_| invalid_data(String::from("Intel HEX file is not text")))?;
+    let mut blocks = Vec::new();
+    let mut base_address = 0u32;
+    let mut entry_point = None;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid_record = |reason: &str| {
+            invalid_data(format!(
+                "Invalid Intel HEX record on line {}: {reason}",
+                index + 1
+            ))
+        };
+        let bytes = line
+            .strip_prefix(':')
+            .and_then(hex_bytes)
+            .ok_or_else(|| invalid_record("not a record"))?;
+        if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+            return Err(invalid_record("wrong length"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(invalid_record("wrong checksum"));
+        }
+
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as u32;
+        let payload = &bytes[4..bytes.len() - 1];
+        let value = || {
+            payload
+                .iter()
+                .fold(0u32, |value, byte| value << 8 | *byte as u32)
+        };
+        match bytes[3] {
+            RECORD_DATA => blocks.push((base_address.wrapping_add(offset), payload.to_vec())),
+            RECORD_END_OF_FILE => break,
+            RECORD_EXTENDED_SEGMENT_ADDRESS => base_address = value() << 4,
+            RECORD_EXTENDED_LINEAR_ADDRESS => base_address = value() << 16,
+            RECORD_START_SEGMENT_ADDRESS => {
+                // CS:IP pair
+                let value = value();
+                entry_point = Some((value >> 16 << 4) + (value & 0xFFFF));
+            }
+            RECORD_START_LINEAR_ADDRESS => entry_point = Some(value()),
+            _ => return Err(invalid_record("unknown record type")),
+        }
+    }
+    Ok(Image::from_blocks(blocks, entry_point))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_split_at_gaps() {
+        let text = ":0200000400807A\n\
+                    :0400000001020304F2\n\
+                    :020004000506EF\n\
+                    :02010000AABB98\n\
+                    :040000058000000077\n\
+                    :00000001FF\n";
+        let image = parse(text.as_bytes()).unwrap();
+        assert_eq!(image.sections.len(), 2);
+        assert_eq!(image.sections[0].address, 0x00800000);
+        assert_eq!(image.sections[0].data, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(image.sections[1].address, 0x00800100);
+        assert_eq!(image.sections[1].data, vec![0xAA, 0xBB]);
+        assert_eq!(image.entry_point, Some(0x80000000));
+    }
+
+    #[test]
+    fn wrong_checksum_is_rejected() {
+        assert!(parse(b":0400000001020304F3\n").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Result;
+
+use crate::input::{Image, hex_bytes, invalid_data};
+
+/// Determines if the data looks like a Motorola S-record file.
+pub fn is_srec(data: &[u8]) -> bool {
+    let data = data.trim_ascii_start();
+    data.first() == Some(&b'S') && data.get(1).is_some_and(u8::is_ascii_digit)
+}
+
+/// Parses a Motorola S-record file (S19, S28 or S37), loading the data
+/// records at their addresses.
+pub fn parse(data: &[u8]) -> Result<Image> {
+    let text = str::from_utf8(data)
+        .map_err(|_| invalid_data(String::from("S-record file is not text")))?;
+    let mut blocks = Vec::new();
+    let mut entry_point = None;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid_record = |reason: &str| {
+            invalid_data(format!("Invalid S-record on line {}: {reason}", index + 1))
+        };
+        let (record_type, bytes) = line
+            .strip_prefix('S')
+            .and_then(|line| line.split_at_checked(1))
+            .and_then(|(record_type, line)| Some((record_type, hex_bytes(line)?)))
+            .ok_or_else(|| invalid_record("not a record"))?;
+        if bytes.len() < 2 || bytes.len() != bytes[0] as usize + 1 {
+            return Err(invalid_record("wrong length"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xFF {
+            return Err(invalid_record("wrong checksum"));
+        }
+
+        let address_size = match record_type {
+            "0" | "1" | "5" | "9" => 2,
+            "2" | "6" | "8" => 3,
+            "3" | "7" => 4,
+            _ => return Err(invalid_record("unknown record type")),
+        };
+        let Some((address, payload)) = bytes[1..bytes.len() - 1].split_at_checked(address_size)
+        else {
+            return Err(invalid_record("wrong length"));
+        };
+        let address = address
+            .iter()
+            .fold(0u32, |value, byte| value << 8 | *byte as u32);
+        match record_type {
+            "1" | "2" | "3" => blocks.push((address, payload.to_vec())),
+            "7" | "8" | "9" => entry_point = Some(address),
+            // Header and record counts
+            _ => (),
+        }
+    }
+    Ok(Image::from_blocks(blocks, entry_point))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_loaded_at_their_address() {
+        let text = "S00600004844521B\n\
+                    S30900800000010203046C\n\
+                    S30700800004050669\n\
+                    S705800000007A\n";
+        let image = parse(text.as_bytes()).unwrap();
+        assert_eq!(image.sections.len(), 1);
+        assert_eq!(image.sections[0].address, 0x00800000);
+        assert_eq!(image.sections[0].data, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(image.entry_point, Some(0x80000000));
+    }
+
+    #[test]
+    fn wrong_checksum_is_rejected() {
+        assert!(parse(b"S30900800000010203046D\n").is_err());
+    }
+}
@@ -1,17 +1,28 @@
+use crate::error::{DecodeError, Result};
 use crate::instruction::fphead::CompactInstructionHeader;
-use crate::instruction::register::Register;
+use crate::instruction::mnemonic::{InstructionClass, Mnemonic};
+use crate::instruction::operand::Operand;
+use crate::instruction::parser::{InstructionFormat, ParsedVariable, parse};
+use crate::instruction::register::{Register, RegisterFile, register_set};
+use crate::instruction::timing::Timing;
+use crate::{COMPACT_INSTRUCTION_SIZE, INSTRUCTION_SIZE};
 use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
-use std::io::{Error, ErrorKind, Result};
 
+pub mod addkpc;
 pub mod branching;
+pub mod dispatch;
 pub mod fphead;
 pub mod invalid;
 pub mod memory;
+pub mod mnemonic;
 pub mod moving;
 pub mod nop;
+pub mod operand;
 pub mod parser;
 pub mod register;
+pub mod timing;
 
 pub trait AsAny {
     fn as_any(&self) -> &dyn Any;
@@ -29,27 +40,142 @@ impl<T: Any> AsAny for T {
 }
 
 pub trait C6000Instruction: AsAny {
-    fn new(_input: &InstructionInput) -> Result<Self>
+    /// Returns the 32-bit formats the instruction can be decoded from.
+    fn formats() -> Vec<InstructionFormat>
     where
         Self: Sized,
     {
-        Err(Error::new(ErrorKind::Unsupported, "Instruction not 32-bit"))
+        Vec::new()
     }
-    fn new_compact(_input: &InstructionInput) -> Result<Self>
+    /// Returns the compact (16-bit) formats the instruction can be decoded from.
+    fn compact_formats() -> Vec<InstructionFormat>
     where
         Self: Sized,
     {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "Instruction not compact (16-bit)",
-        ))
+        Vec::new()
+    }
+    /// Builds the instruction from the variables parsed using the
+    /// 32-bit format named `format`.
+    fn from_format(
+        _format: &str,
+        _input: &InstructionInput,
+        _parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(DecodeError::Unsupported {
+            reason: "Instruction not 32-bit",
+        })
+    }
+    /// Builds the instruction from the variables parsed using the
+    /// compact format named `format`.
+    fn from_compact_format(
+        _format: &str,
+        _input: &InstructionInput,
+        _parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(DecodeError::Unsupported {
+            reason: "Instruction not compact (16-bit)",
+        })
+    }
+    fn new(input: &InstructionInput) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        for format in Self::formats() {
+            if let Ok(parsed_variables) = parse(input.opcode, &format.parsing_instructions) {
+                return Self::from_format(format.name, input, &parsed_variables);
+            }
+        }
+        Err(DecodeError::OpcodeMismatch {
+            opcode: input.opcode,
+            reason: String::from("No matching 32-bit instruction format"),
+        })
+    }
+    fn new_compact(input: &InstructionInput) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        for format in Self::compact_formats() {
+            if let Ok(parsed_variables) = parse(input.opcode, &format.parsing_instructions) {
+                return Self::from_compact_format(format.name, input, &parsed_variables);
+            }
+        }
+        Err(DecodeError::OpcodeMismatch {
+            opcode: input.opcode,
+            reason: String::from("No matching compact instruction format"),
+        })
+    }
+    fn mnemonic(&self) -> Mnemonic;
+    /// Returns the coarse class of the instruction, derived from its mnemonic.
+    fn class(&self) -> InstructionClass {
+        self.mnemonic().class()
     }
     fn instruction(&self) -> String;
+    /// Returns the pipeline timing of the instruction.
+    fn timing(&self) -> Timing {
+        self.mnemonic().timing()
+    }
+    /// Returns the functional unit executing the instruction.
+    fn unit(&self) -> Option<Unit> {
+        None
+    }
+    /// Returns the side of the functional unit (false for side 1, true for side 2).
+    fn side(&self) -> Option<bool> {
+        None
+    }
+    /// Determines if an operand is read from the opposite register file
+    /// through the cross path.
+    fn uses_crosspath(&self) -> bool {
+        false
+    }
+    /// Returns the side of the register file used for data by a load or a store
+    /// (the ``T1``/``T2`` suffix of .D instructions).
+    fn data_path_side(&self) -> Option<bool> {
+        None
+    }
     fn instruction_clean(&self) -> String {
-        self.instruction()
+        self.mnemonic().to_string()
+    }
+    /// Returns the operands of the instruction, in the order they are written.
+    fn operand_list(&self) -> Vec<Operand> {
+        Vec::new()
+    }
+    /// Returns a note written after the operands, such as the offset
+    /// computed by a memory access.
+    fn comment(&self) -> Option<String> {
+        None
     }
     fn operands(&self) -> String {
-        String::from("")
+        let operands = self
+            .operand_list()
+            .iter()
+            .map(|operand| operand.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        match self.comment() {
+            Some(comment) => format!("{operands} {comment}"),
+            None => operands,
+        }
+    }
+    /// Returns the register tested by the condition of the instruction, if any.
+    fn condition_register(&self) -> Option<Register> {
+        self.conditional_operation()
+            .and_then(|conditional_operation| conditional_operation.register())
+    }
+    /// Returns the registers read by the instruction, including implicit ones
+    /// (such as the condition register). Register pairs are split.
+    fn reads(&self) -> HashSet<RegisterFile> {
+        register_set(self.condition_register().map(RegisterFile::from))
+    }
+    /// Returns the registers written by the instruction, including implicit ones
+    /// (such as the return address of ``CALLP``). Register pairs are split.
+    fn writes(&self) -> HashSet<RegisterFile> {
+        HashSet::new()
     }
     fn instruction_data(&self) -> &InstructionData;
     fn instruction_data_mut(&mut self) -> &mut InstructionData;
@@ -59,6 +185,14 @@ pub trait C6000Instruction: AsAny {
     fn is_compact(&self) -> bool {
         self.instruction_data().compact
     }
+    /// Returns the size of the instruction in bytes.
+    fn size(&self) -> usize {
+        if self.is_compact() {
+            COMPACT_INSTRUCTION_SIZE
+        } else {
+            INSTRUCTION_SIZE
+        }
+    }
     fn is_parallel(&self) -> bool {
         self.instruction_data().parallel
     }
@@ -79,7 +213,7 @@ pub struct InstructionInput {
     pub pce1_address: u32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct InstructionData {
     pub opcode: u32,
     pub compact: bool,
@@ -89,18 +223,6 @@ pub struct InstructionData {
     pub conditional_operation: Option<ConditionalOperation>,
 }
 
-impl Default for InstructionData {
-    fn default() -> Self {
-        Self {
-            opcode: 0,
-            compact: false,
-            parallel: false,
-            p_bit: false,
-            conditional_operation: None,
-        }
-    }
-}
-
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum DataSize {
     Byte,
@@ -114,16 +236,17 @@ pub enum DataSize {
 }
 
 impl DataSize {
-    fn to_short_string(&self) -> String {
+    /// Returns the suffix used by load and store mnemonics.
+    pub fn short_name(self) -> &'static str {
         match self {
-            Self::Byte => String::from("B"),
-            Self::ByteUnsigned => String::from("BU"),
-            Self::HalfWord => String::from("H"),
-            Self::HalfWordUnsigned => String::from("HU"),
-            Self::Word => String::from("W"),
-            Self::NonAlignedWord => String::from("NW"),
-            Self::DoubleWord => String::from("DW"),
-            Self::NonAlignedDoubleWord => String::from("NDW"),
+            Self::Byte => "B",
+            Self::ByteUnsigned => "BU",
+            Self::HalfWord => "H",
+            Self::HalfWordUnsigned => "HU",
+            Self::Word => "W",
+            Self::NonAlignedWord => "NW",
+            Self::DoubleWord => "DW",
+            Self::NonAlignedDoubleWord => "NDW",
         }
     }
 }
@@ -143,7 +266,7 @@ impl Display for DataSize {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Unit {
     L,
     S,
@@ -154,7 +277,7 @@ pub enum Unit {
 impl Unit {
     pub fn to_sided_string(&self, side: bool) -> String {
         let mut value = self.to_string();
-        if side == false {
+        if !side {
             value += "1";
         } else {
             value += "2";
@@ -174,7 +297,7 @@ impl Display for Unit {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum ConditionalOperation {
     ReservedLow,
     ReservedHigh,
@@ -184,7 +307,7 @@ pub enum ConditionalOperation {
 
 impl ConditionalOperation {
     pub fn from(creg: u8, z: bool) -> Option<Self> {
-        if creg == 0 && z == true {
+        if creg == 0 && z {
             return Some(ConditionalOperation::ReservedLow);
         } else if creg == 0b111 {
             return Some(ConditionalOperation::ReservedHigh);
@@ -217,6 +340,14 @@ impl ConditionalOperation {
             None
         }
     }
+
+    /// Returns the register tested by the condition.
+    pub fn register(&self) -> Option<Register> {
+        match self {
+            Self::Zero(register) | Self::NonZero(register) => Some(*register),
+            Self::ReservedLow | Self::ReservedHigh => None,
+        }
+    }
 }
 
 impl Display for ConditionalOperation {
@@ -228,3 +359,76 @@ impl Display for ConditionalOperation {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_compact_instruction, read_instruction};
+    use memory::AddressGeneratorMode;
+
+    fn decode(opcode: u32) -> Box<dyn C6000Instruction> {
+        read_instruction(InstructionInput {
+            opcode,
+            fphead: None,
+            pce1_address: 0x1000,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn instructions_report_their_unit_side_and_operands() {
+        // LDW.D2T1 *++B15[1], A4
+        let load = decode(0x023C32E4);
+        assert_eq!((load.unit(), load.side()), (Some(Unit::D), Some(true)));
+        assert_eq!(load.data_path_side(), Some(false));
+        assert!(!load.uses_crosspath());
+        assert_eq!(
+            load.operand_list(),
+            vec![
+                Operand::Memory {
+                    base_register: Register::B(15),
+                    mode: AddressGeneratorMode::Preincrement(1),
+                },
+                Operand::Register(Register::A(4)),
+            ]
+        );
+
+        // B.S1 0x00001014
+        let branch = decode(0x00000290);
+        assert_eq!((branch.unit(), branch.side()), (Some(Unit::S), Some(false)));
+        assert_eq!(
+            branch.operand_list(),
+            vec![Operand::BranchTarget {
+                pce1_address: 0x1000,
+                displacement: 0x14,
+            }]
+        );
+
+        // Compact MV.L1 A7, A6
+        let header = CompactInstructionHeader::new(&InstructionInput {
+            opcode: 0xE0000000,
+            fphead: None,
+            pce1_address: 0x1000,
+        })
+        .unwrap();
+        let compact = read_compact_instruction(InstructionInput {
+            opcode: 0xC386,
+            fphead: Some(header),
+            pce1_address: 0x1000,
+        })
+        .unwrap();
+        assert_eq!(compact.instruction(), "MV.L1");
+        assert_eq!(
+            (compact.unit(), compact.side()),
+            (Some(Unit::L), Some(false))
+        );
+        assert!(!compact.uses_crosspath());
+        assert_eq!(
+            compact.operand_list(),
+            vec![
+                Operand::Register(Register::A(7)),
+                Operand::Register(Register::A(6)),
+            ]
+        );
+    }
+}
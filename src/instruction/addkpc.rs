@@ -0,0 +1,152 @@
+use crate::error::Result;
+use crate::instruction::{
+    C6000Instruction, InstructionData, InstructionInput, Unit,
+    mnemonic::Mnemonic,
+    operand::Operand,
+    parser::{InstructionFormat, ParsedVariable, ParsingInstruction},
+    register::{Register, RegisterFile, register_set},
+    timing::Timing,
+};
+use std::collections::{HashMap, HashSet};
+
+/// ``ADDKPC``, adding a displacement to the PCE1 address, mostly used to
+/// set up the return address of a call in ``B3``.
+pub struct ADDKPCInstruction {
+    /// Displacement from the PCE1 address, in bytes.
+    pub displacement: i32,
+    pub destination: Register,
+    pub nop_count: u8,
+    pce1_address: u32,
+    instruction_data: InstructionData,
+}
+
+impl C6000Instruction for ADDKPCInstruction {
+    fn formats() -> Vec<InstructionFormat> {
+        vec![InstructionFormat::new(
+            "Saddkpc",
+            vec![
+                ParsingInstruction::Bit {
+                    name: String::from("p"),
+                },
+                ParsingInstruction::BitMatch {
+                    name: String::from("s"),
+                    value: true,
+                },
+                ParsingInstruction::Match {
+                    size: 11,
+                    value: 0x58,
+                },
+                ParsingInstruction::Unsigned {
+                    size: 3,
+                    name: String::from("src2"),
+                },
+                ParsingInstruction::Signed {
+                    size: 7,
+                    name: String::from("src1"),
+                },
+                ParsingInstruction::Register {
+                    size: 5,
+                    name: String::from("dst"),
+                },
+                ParsingInstruction::ConditionalOperation {
+                    name: String::from("cond"),
+                },
+            ],
+        )]
+    }
+
+    fn from_format(
+        _format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+        let nop_count = ParsedVariable::try_get(parsed_variables, "src2")?.get_u8()?;
+        let displacement = ParsedVariable::try_get(parsed_variables, "src1")?.get_i32()? << 2;
+        let destination = ParsedVariable::try_get(parsed_variables, "dst")?.get_register()?;
+        let conditional_operation =
+            ParsedVariable::try_get(parsed_variables, "cond")?.get_conditional_operation()?;
+        Ok(Self {
+            displacement,
+            destination,
+            nop_count,
+            pce1_address: input.pce1_address,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                conditional_operation,
+                p_bit,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn mnemonic(&self) -> Mnemonic {
+        Mnemonic::ADDKPC
+    }
+
+    fn instruction(&self) -> String {
+        format!("{}.S2", self.instruction_clean())
+    }
+
+    fn timing(&self) -> Timing {
+        self.mnemonic().timing().with_nop_cycles(self.nop_count)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::S)
+    }
+
+    fn side(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn writes(&self) -> HashSet<RegisterFile> {
+        register_set([RegisterFile::from(self.destination)])
+    }
+
+    fn operand_list(&self) -> Vec<Operand> {
+        vec![
+            Operand::BranchTarget {
+                pce1_address: self.pce1_address,
+                displacement: self.displacement,
+            },
+            Operand::from(self.destination),
+            Operand::Cycles(self.nop_count),
+        ]
+    }
+
+    fn instruction_data(&self) -> &InstructionData {
+        &self.instruction_data
+    }
+
+    fn instruction_data_mut(&mut self) -> &mut InstructionData {
+        &mut self.instruction_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_instruction;
+
+    #[test]
+    fn addkpc_writes_return_address() {
+        // ADDKPC.S2 0x0000100C, B3, 4
+        let instruction = read_instruction(InstructionInput {
+            opcode: 0x01838162,
+            fphead: None,
+            pce1_address: 0x1000,
+        })
+        .unwrap();
+        assert_eq!(instruction.instruction(), "ADDKPC.S2");
+        assert_eq!(
+            instruction.operands(),
+            "0x0000100C (PCE1+0x0000000C), B3, 4"
+        );
+        assert_eq!(
+            instruction.writes(),
+            register_set([RegisterFile::from(Register::B(3))])
+        );
+        assert_eq!(instruction.timing().nop_cycles, 4);
+    }
+}
@@ -1,12 +1,13 @@
-use std::{
-    cmp::min,
-    io::{self, Error, ErrorKind},
-};
+use std::collections::{HashMap, HashSet};
 
+use crate::error::{self, DecodeError};
 use crate::instruction::{
-    C6000Instruction, ConditionalOperation, InstructionData,
-    parser::{ParsedVariable, ParsingInstruction, parse},
-    register::{ControlRegister, Register},
+    C6000Instruction, ConditionalOperation, InstructionData, InstructionInput, Unit,
+    mnemonic::Mnemonic,
+    operand::Operand,
+    parser::{InstructionFormat, ParsedVariable, ParsingInstruction},
+    register::{ControlRegister, Register, RegisterFile, register_set},
+    timing::Timing,
 };
 
 pub enum BranchUsing {
@@ -24,209 +25,169 @@ pub struct BranchInstruction {
 }
 
 impl BranchInstruction {
-    pub fn calculate_displacement_address(&self) -> io::Result<u32> {
+    pub fn calculate_displacement_address(&self) -> error::Result<u32> {
         match self.branch_using {
             BranchUsing::Displacement(displacement) => {
-                let displacement_abs = displacement.unsigned_abs();
                 if self.pce1_address == 0 {
-                    return Err(Error::other("PCE1 = 0"));
-                };
-                let branch_address = {
-                    if displacement.is_positive() {
-                        self.pce1_address + displacement_abs
-                    } else {
-                        self.pce1_address - displacement_abs
-                    }
+                    return Err(DecodeError::UnknownPce1);
                 };
-                return Ok(branch_address);
+                let branch_address = self.pce1_address.wrapping_add_signed(displacement);
+                Ok(branch_address)
             }
-            _ => return Err(Error::other("Not displacement")),
+            _ => Err(DecodeError::NotDisplacement),
         }
     }
+
+    /// Returns the address the instruction branches to, if it is a branch
+    /// using a displacement from a known PCE1 address.
+    pub fn target_of(instruction: &dyn C6000Instruction) -> Option<u32> {
+        instruction
+            .as_any()
+            .downcast_ref::<Self>()
+            .and_then(|branch| branch.calculate_displacement_address().ok())
+    }
 }
 
 impl C6000Instruction for BranchInstruction {
-    fn new(input: &super::InstructionInput) -> std::io::Result<Self> {
-        let formats = [
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match {
-                    size: 5,
-                    value: 0b100,
-                },
-                ParsingInstruction::Signed {
-                    size: 21,
-                    name: String::from("cst"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match {
-                    size: 10,
-                    value: 0xD8,
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("x"),
-                },
-                ParsingInstruction::Match { size: 5, value: 0 },
-                ParsingInstruction::RegisterCrosspath {
-                    size: 5,
-                    name: String::from("src"),
-                },
-                ParsingInstruction::Match { size: 5, value: 0 },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::BitMatch {
-                    name: String::from("s"),
-                    value: true,
-                },
-                ParsingInstruction::Match {
-                    size: 16,
-                    value: 0b111000,
-                },
-                ParsingInstruction::Unsigned {
-                    size: 3,
-                    name: String::from("op"),
-                },
-                ParsingInstruction::Match { size: 7, value: 0 },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match {
-                    size: 11,
-                    value: 0x48,
-                },
-                ParsingInstruction::Unsigned {
-                    size: 3,
-                    name: String::from("nop"),
-                },
-                ParsingInstruction::Signed {
-                    size: 12,
-                    name: String::from("cst"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::BitMatch {
-                    name: String::from("s"),
-                    value: true,
-                },
-                ParsingInstruction::Match {
-                    size: 10,
-                    value: 0xD8,
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("x"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 3,
-                    name: String::from("nop"),
-                },
-                ParsingInstruction::Match { size: 2, value: 0 },
-                ParsingInstruction::RegisterCrosspath {
-                    size: 5,
-                    name: String::from("src"),
-                },
-                ParsingInstruction::Match { size: 5, value: 1 },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-        ];
-        for format in formats {
-            let Ok(parsed_variables) = parse(input.opcode, format.as_slice()) else {
-                continue;
-            };
-            let p_bit = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool()?;
-            let side = ParsedVariable::try_get(&parsed_variables, "s")?.get_bool()?;
-            let conditional_operation =
-                ParsedVariable::try_get(&parsed_variables, "cond")?.get_conditional_operation()?;
-            let nop_count = {
-                if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "nop") {
-                    variable.get_u8()?
-                } else {
-                    0
-                }
-            };
-            let branch_using = {
-                if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "cst") {
-                    BranchUsing::Displacement(
-                        variable.get_i32()? << {
-                            if nop_count > 0 && input.fphead.is_some() {
-                                1
-                            } else {
-                                2
-                            }
-                        },
-                    )
-                } else if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "src") {
-                    BranchUsing::Register(variable.get_register()?)
-                } else if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "op") {
-                    let opcode = variable.get_u8()?;
-                    match opcode {
-                        0b110 => BranchUsing::Pointer(ControlRegister::IRP),
-                        0b111 => BranchUsing::Pointer(ControlRegister::NRP),
-                        _ => continue,
-                    }
-                } else {
-                    continue;
-                }
-            };
-            return Ok(Self {
-                side,
-                branch_using,
-                pce1_address: input.pce1_address,
-                nop_count,
-                instruction_data: InstructionData {
-                    opcode: input.opcode,
-                    compact: false,
-                    conditional_operation,
-                    p_bit,
-                    ..Default::default()
-                },
-            });
-        }
-        Err(Error::new(
-            ErrorKind::InvalidInput,
-            "Not a branch instruction",
-        ))
+    fn formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "Sdisp",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 5,
+                        value: 0b100,
+                    },
+                    ParsingInstruction::Signed {
+                        size: 21,
+                        name: String::from("cst"),
+                    },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "Sreg",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 10,
+                        value: 0xD8,
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("x"),
+                    },
+                    ParsingInstruction::Match { size: 5, value: 0 },
+                    ParsingInstruction::RegisterCrosspath {
+                        size: 5,
+                        name: String::from("src"),
+                    },
+                    ParsingInstruction::Match { size: 5, value: 0 },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "Sirp",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::BitMatch {
+                        name: String::from("s"),
+                        value: true,
+                    },
+                    ParsingInstruction::Match {
+                        size: 16,
+                        value: 0b111000,
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 3,
+                        name: String::from("op"),
+                    },
+                    ParsingInstruction::Match { size: 7, value: 0 },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "Sdispnop",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 11,
+                        value: 0x48,
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 3,
+                        name: String::from("nop"),
+                    },
+                    ParsingInstruction::Signed {
+                        size: 12,
+                        name: String::from("cst"),
+                    },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "Sregnop",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::BitMatch {
+                        name: String::from("s"),
+                        value: true,
+                    },
+                    ParsingInstruction::Match {
+                        size: 10,
+                        value: 0xD8,
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("x"),
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 3,
+                        name: String::from("nop"),
+                    },
+                    ParsingInstruction::Match { size: 2, value: 0 },
+                    ParsingInstruction::RegisterCrosspath {
+                        size: 5,
+                        name: String::from("src"),
+                    },
+                    ParsingInstruction::Match { size: 5, value: 1 },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+        ]
     }
 
-    fn new_compact(input: &super::InstructionInput) -> std::io::Result<Self> {
-        let formats = [
-            (
+    fn compact_formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
                 "sbs7",
                 vec![
                     ParsingInstruction::Bit {
@@ -240,13 +201,15 @@ impl C6000Instruction for BranchInstruction {
                         size: 7,
                         name: String::from("cst"),
                     },
-                    ParsingInstruction::Unsigned {
+                    ParsingInstruction::UnsignedRange {
                         size: 3,
                         name: String::from("nop"),
+                        min: 0,
+                        max: 5,
                     },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "sbu8",
                 vec![
                     ParsingInstruction::Bit {
@@ -266,7 +229,7 @@ impl C6000Instruction for BranchInstruction {
                     },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "scs10",
                 vec![
                     ParsingInstruction::Bit {
@@ -282,7 +245,7 @@ impl C6000Instruction for BranchInstruction {
                     },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "sbs7c",
                 vec![
                     ParsingInstruction::Bit {
@@ -300,13 +263,15 @@ impl C6000Instruction for BranchInstruction {
                         size: 7,
                         name: String::from("cst"),
                     },
-                    ParsingInstruction::Unsigned {
+                    ParsingInstruction::UnsignedRange {
                         size: 3,
                         name: String::from("nop"),
+                        min: 0,
+                        max: 5,
                     },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "sbu8c",
                 vec![
                     ParsingInstruction::Bit {
@@ -330,7 +295,7 @@ impl C6000Instruction for BranchInstruction {
                     },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "sx1b",
                 vec![
                     ParsingInstruction::Bit {
@@ -345,132 +310,220 @@ impl C6000Instruction for BranchInstruction {
                         name: String::from("src"),
                     },
                     ParsingInstruction::Match { size: 2, value: 0 },
-                    ParsingInstruction::Unsigned {
+                    ParsingInstruction::UnsignedRange {
                         size: 3,
                         name: String::from("nop"),
+                        min: 0,
+                        max: 5,
                     },
                 ],
             ),
-        ];
+        ]
+    }
 
-        for (name, format) in formats {
-            let Ok(parsed_variables) = parse(input.opcode, format.as_slice()) else {
-                continue;
-            };
-            let side = ParsedVariable::try_get(&parsed_variables, "s")?.get_bool()?;
-            let branch_using = {
-                if name == "sx1b" {
-                    let Ok(src) = ParsedVariable::try_get(&parsed_variables, "src") else {
-                        continue;
-                    };
-                    BranchUsing::Register(Register::B(src.get_u8()?))
-                } else {
-                    let Ok(cst) = ParsedVariable::try_get(&parsed_variables, "cst") else {
-                        continue;
-                    };
-                    BranchUsing::Displacement(if name == "sbu8" || name == "sbu8c" {
-                        (cst.get_u8()? as i32) << 1
-                    } else {
-                        cst.get_i32()? << { if name == "scs10" { 2 } else { 1 } }
-                    })
-                }
-            };
-            let nop_count = {
-                if name == "sbs7" || name == "sbs7c" || name == "sx1b" {
-                    let Ok(nop) = ParsedVariable::try_get(&parsed_variables, "nop") else {
-                        continue;
-                    };
-                    min(nop.get_u8()?, 5)
-                } else {
-                    5
-                }
-            };
-            let conditional_operation = {
-                if name == "scs10" {
-                    Some(ConditionalOperation::ReservedLow)
-                } else if name == "sbs7c" || name == "sbu8c" {
-                    let z = ParsedVariable::try_get(&parsed_variables, "z")?.get_bool()?;
-                    if z {
-                        Some(ConditionalOperation::Zero(Register::from(0, side)))
-                    } else {
-                        Some(ConditionalOperation::NonZero(Register::from(0, side)))
+    fn from_format(
+        _format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> error::Result<Self> {
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+        let side = ParsedVariable::try_get(parsed_variables, "s")?.get_bool()?;
+        let conditional_operation =
+            ParsedVariable::try_get(parsed_variables, "cond")?.get_conditional_operation()?;
+        let nop_count = {
+            if let Ok(variable) = ParsedVariable::try_get(parsed_variables, "nop") {
+                variable.get_u8()?
+            } else {
+                0
+            }
+        };
+        let branch_using = {
+            if let Ok(variable) = ParsedVariable::try_get(parsed_variables, "cst") {
+                BranchUsing::Displacement(
+                    variable.get_i32()? << {
+                        if nop_count > 0 && input.fphead.is_some() {
+                            1
+                        } else {
+                            2
+                        }
+                    },
+                )
+            } else if let Ok(variable) = ParsedVariable::try_get(parsed_variables, "src") {
+                BranchUsing::Register(variable.get_register()?)
+            } else if let Ok(variable) = ParsedVariable::try_get(parsed_variables, "op") {
+                let opcode = variable.get_u8()?;
+                match opcode {
+                    0b110 => BranchUsing::Pointer(ControlRegister::IRP),
+                    0b111 => BranchUsing::Pointer(ControlRegister::NRP),
+                    _ => {
+                        return Err(DecodeError::ReservedField {
+                            opcode: input.opcode,
+                            field: String::from("op"),
+                            value: opcode as u32,
+                        });
                     }
-                } else {
-                    None
                 }
-            };
-            return Ok(Self {
-                instruction_data: InstructionData {
+            } else {
+                return Err(DecodeError::OpcodeMismatch {
                     opcode: input.opcode,
-                    compact: true,
-                    conditional_operation,
-                    ..Default::default()
-                },
-                branch_using,
-                side,
-                pce1_address: input.pce1_address,
-                nop_count,
-            });
-        }
+                    reason: String::from("Not a branch instruction"),
+                });
+            }
+        };
+        Ok(Self {
+            side,
+            branch_using,
+            pce1_address: input.pce1_address,
+            nop_count,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                compact: false,
+                conditional_operation,
+                p_bit,
+                ..Default::default()
+            },
+        })
+    }
 
-        Err(Error::new(
-            ErrorKind::InvalidInput,
-            "Not a branch instruction",
-        ))
+    fn from_compact_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> error::Result<Self> {
+        let side = ParsedVariable::try_get(parsed_variables, "s")?.get_bool()?;
+        let branch_using = {
+            if format == "sx1b" {
+                let src = ParsedVariable::try_get(parsed_variables, "src")?;
+                BranchUsing::Register(Register::B(src.get_u8()?))
+            } else {
+                let cst = ParsedVariable::try_get(parsed_variables, "cst")?;
+                BranchUsing::Displacement(if format == "sbu8" || format == "sbu8c" {
+                    (cst.get_u8()? as i32) << 1
+                } else {
+                    cst.get_i32()? << { if format == "scs10" { 2 } else { 1 } }
+                })
+            }
+        };
+        let nop_count = {
+            if format == "sbs7" || format == "sbs7c" || format == "sx1b" {
+                ParsedVariable::try_get(parsed_variables, "nop")?.get_u8()?
+            } else {
+                5
+            }
+        };
+        let conditional_operation = {
+            if format == "scs10" {
+                Some(ConditionalOperation::ReservedLow)
+            } else if format == "sbs7c" || format == "sbu8c" {
+                let z = ParsedVariable::try_get(parsed_variables, "z")?.get_bool()?;
+                if z {
+                    Some(ConditionalOperation::Zero(Register::from(0, side)))
+                } else {
+                    Some(ConditionalOperation::NonZero(Register::from(0, side)))
+                }
+            } else {
+                None
+            }
+        };
+        Ok(Self {
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                compact: true,
+                conditional_operation,
+                ..Default::default()
+            },
+            branch_using,
+            side,
+            pce1_address: input.pce1_address,
+            nop_count,
+        })
     }
 
-    fn instruction_clean(&self) -> String {
+    fn mnemonic(&self) -> Mnemonic {
         if let Some(co) = self.conditional_operation()
             && co == ConditionalOperation::ReservedLow
         {
-            String::from("CALLP")
+            Mnemonic::CALLP
+        } else if self.nop_count > 0 {
+            Mnemonic::BNOP
         } else {
-            if self.nop_count > 0 {
-                String::from("BNOP")
-            } else {
-                String::from("B")
-            }
+            Mnemonic::B
         }
     }
 
     fn instruction(&self) -> String {
-        let unit_num = if self.side { 2 } else { 1 };
-        let mut instruction = format!("{}.S{unit_num}", self.instruction_clean());
-        if let BranchUsing::Register(register) = self.branch_using
-            && register.side() != self.side
-        {
+        let mut instruction = format!(
+            "{}.{}",
+            self.instruction_clean(),
+            Unit::S.to_sided_string(self.side)
+        );
+        if self.uses_crosspath() {
             instruction += "X";
         }
         instruction
     }
 
-    fn operands(&self) -> String {
-        let operands = match self.branch_using {
-            BranchUsing::Displacement(displacement) => {
-                let displacement_abs = displacement.unsigned_abs();
-                let address_result = self.calculate_displacement_address();
-                let Ok(branch_address) = self.calculate_displacement_address() else {
-                    return address_result
-                        .map_err(|e| format!("ERROR {e}"))
-                        .unwrap_err();
-                };
-                format!(
-                    "0x{branch_address:08X} (PCE1{}0x{displacement_abs:08X})",
-                    if displacement.is_positive() { "+" } else { "-" }
-                )
-            }
-            BranchUsing::Register(register) => register.to_string(),
-            BranchUsing::Pointer(register) => register.to_string(),
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::S)
+    }
+
+    fn side(&self) -> Option<bool> {
+        Some(self.side)
+    }
+
+    fn uses_crosspath(&self) -> bool {
+        matches!(self.branch_using, BranchUsing::Register(register) if register.side() != self.side)
+    }
+
+    fn timing(&self) -> Timing {
+        let timing = self.mnemonic().timing();
+        match self.mnemonic() {
+            // CALLP fills all of its delay slots with NOPs
+            Mnemonic::CALLP => timing.with_nop_cycles(timing.delay_slots),
+            _ => timing.with_nop_cycles(self.nop_count),
+        }
+    }
+
+    fn reads(&self) -> HashSet<RegisterFile> {
+        let target = match self.branch_using {
+            BranchUsing::Displacement(_) => None,
+            BranchUsing::Register(register) => Some(RegisterFile::from(register)),
+            BranchUsing::Pointer(register) => Some(RegisterFile::Control(register)),
+        };
+        register_set(
+            self.condition_register()
+                .map(RegisterFile::from)
+                .into_iter()
+                .chain(target),
+        )
+    }
+
+    fn writes(&self) -> HashSet<RegisterFile> {
+        if self.mnemonic() == Mnemonic::CALLP {
+            register_set([RegisterFile::from(Register::from(3, self.side))])
+        } else {
+            HashSet::new()
+        }
+    }
+
+    fn operand_list(&self) -> Vec<Operand> {
+        let target = match self.branch_using {
+            BranchUsing::Displacement(displacement) => Operand::BranchTarget {
+                pce1_address: self.pce1_address,
+                displacement,
+            },
+            BranchUsing::Register(register) => Operand::from(register),
+            BranchUsing::Pointer(register) => Operand::ControlRegister(register),
         };
 
         if let Some(co) = self.conditional_operation()
             && co == ConditionalOperation::ReservedLow
         {
-            format!("{operands}, {}", Register::from(3, self.side).to_string())
+            vec![target, Operand::from(Register::from(3, self.side))]
         } else if self.nop_count > 0 {
-            format!("{operands}, {}", self.nop_count)
+            vec![target, Operand::Cycles(self.nop_count)]
         } else {
-            operands
+            vec![target]
         }
     }
 
@@ -482,3 +535,70 @@ impl C6000Instruction for BranchInstruction {
         &mut self.instruction_data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instruction::fphead::CompactInstructionHeader, read_compact_instruction};
+
+    fn decode_compact(opcode: u32) -> String {
+        let fphead = CompactInstructionHeader::new(&InstructionInput {
+            opcode: 0xE0000000,
+            fphead: None,
+            pce1_address: 0x1000,
+        })
+        .unwrap();
+        let instruction = read_compact_instruction(InstructionInput {
+            opcode,
+            fphead: Some(fphead),
+            pce1_address: 0x1000,
+        })
+        .unwrap();
+        format!("{} {}", instruction.instruction(), instruction.operands())
+    }
+
+    #[test]
+    fn sbs7_nop_counts_above_five_select_sbu8() {
+        // Previously decoded through Sbs7, with a NOP count of 6 clamped to 5,
+        // as BNOP.S1 0x00000F80 (PCE1-0x00000080), 5
+        assert_eq!(
+            decode_compact(0xD00A),
+            "BNOP.S1 0x00001080 (PCE1+0x00000080), 5"
+        );
+        assert_eq!(
+            decode_compact(0xB00A),
+            "BNOP.S1 0x00000F80 (PCE1-0x00000080), 5"
+        );
+    }
+
+    #[test]
+    fn sx1b_rejects_nop_counts_above_five() {
+        assert_eq!(decode_compact(0xA1EF), "BNOP.S2 B3, 5");
+        // Previously clamped to BNOP.S2 B3, 5
+        assert_eq!(decode_compact(0xC1EF), "INVALID COMPACT INSTRUCTION ");
+        assert_eq!(decode_compact(0xE1EF), "INVALID COMPACT INSTRUCTION ");
+    }
+
+    fn displacement_address(opcode: u32, pce1_address: u32) -> error::Result<u32> {
+        let input = InstructionInput {
+            opcode,
+            fphead: None,
+            pce1_address,
+        };
+        BranchInstruction::new(&input)?.calculate_displacement_address()
+    }
+
+    #[test]
+    fn displacement_address_is_relative_to_pce1() {
+        assert_eq!(displacement_address(0x00000290, 0x1000), Ok(0x1014));
+        assert_eq!(
+            displacement_address(0x00000290, 0),
+            Err(DecodeError::UnknownPce1)
+        );
+        // B.S2 B3
+        assert_eq!(
+            displacement_address(0x000C0362, 0x1000),
+            Err(DecodeError::NotDisplacement)
+        );
+    }
+}
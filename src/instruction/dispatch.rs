@@ -0,0 +1,259 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use crate::error::Result;
+use crate::instruction::{
+    C6000Instruction, InstructionInput,
+    addkpc::ADDKPCInstruction,
+    branching::BranchInstruction,
+    memory::MemoryInstruction,
+    moving::{MoveConstantInstruction, MoveRegisterInstruction},
+    nop::NOPInstruction,
+    parser::{InstructionFormat, ParsedVariable, parse},
+};
+
+type Decoder = fn(
+    &str,
+    &InstructionInput,
+    &HashMap<String, ParsedVariable>,
+) -> Result<Box<dyn C6000Instruction>>;
+
+fn decode_as<T: C6000Instruction + 'static>(
+    format: &str,
+    input: &InstructionInput,
+    parsed_variables: &HashMap<String, ParsedVariable>,
+) -> Result<Box<dyn C6000Instruction>> {
+    Ok(Box::new(T::from_format(format, input, parsed_variables)?))
+}
+
+fn decode_compact_as<T: C6000Instruction + 'static>(
+    format: &str,
+    input: &InstructionInput,
+    parsed_variables: &HashMap<String, ParsedVariable>,
+) -> Result<Box<dyn C6000Instruction>> {
+    Ok(Box::new(T::from_compact_format(
+        format,
+        input,
+        parsed_variables,
+    )?))
+}
+
+/// Fixed opcode bits of a registered format.
+///
+/// Formats containing a value set (such as ``MatchMultiple``) are
+/// registered once for every value they accept.
+struct Pattern {
+    mask: u32,
+    value: u32,
+    format_index: usize,
+}
+
+impl Pattern {
+    fn matches(&self, opcode: u32) -> bool {
+        opcode & self.mask == self.value
+    }
+}
+
+enum DispatchNode {
+    Leaf(Vec<usize>),
+    Split {
+        bit: u32,
+        zero: Box<DispatchNode>,
+        one: Box<DispatchNode>,
+    },
+}
+
+/// Decision tree selecting the instruction format of an opcode
+/// using the fixed bits (opcode fields, unit selectors) of every
+/// registered format.
+pub struct DispatchTable {
+    formats: Vec<(InstructionFormat, Decoder)>,
+    patterns: Vec<Pattern>,
+    root: DispatchNode,
+}
+
+impl DispatchTable {
+    fn new(formats: Vec<(InstructionFormat, Decoder)>) -> Self {
+        let mut patterns = Vec::new();
+        for (format_index, (format, _)) in formats.iter().enumerate() {
+            for (mask, value) in format.fixed_bits() {
+                patterns.push(Pattern {
+                    mask,
+                    value,
+                    format_index,
+                });
+            }
+        }
+        let root = Self::build_node((0..patterns.len()).collect(), &patterns, 0);
+        Self {
+            formats,
+            patterns,
+            root,
+        }
+    }
+
+    /// Splits the candidate patterns on the bit which is fixed in the most of them
+    /// and has both values present, until no such bit remains.
+    fn build_node(candidates: Vec<usize>, patterns: &[Pattern], used_bits: u32) -> DispatchNode {
+        if candidates.len() <= 1 {
+            return DispatchNode::Leaf(candidates);
+        }
+
+        let mut best_bit = None;
+        let mut best_count = 0;
+        for bit in 0..32 {
+            let bit_mask = 1 << bit;
+            if used_bits & bit_mask != 0 {
+                continue;
+            }
+            let fixed = candidates
+                .iter()
+                .map(|index| &patterns[*index])
+                .filter(|pattern| pattern.mask & bit_mask != 0);
+            let ones = fixed
+                .clone()
+                .filter(|pattern| pattern.value & bit_mask != 0)
+                .count();
+            let count = fixed.count();
+            if ones > 0 && ones < count && count > best_count {
+                best_bit = Some(bit);
+                best_count = count;
+            }
+        }
+
+        let Some(bit) = best_bit else {
+            return DispatchNode::Leaf(candidates);
+        };
+        let bit_mask = 1 << bit;
+        let (mut zero, mut one) = (Vec::new(), Vec::new());
+        for index in candidates {
+            let pattern = &patterns[index];
+            if pattern.mask & bit_mask == 0 {
+                zero.push(index);
+                one.push(index);
+            } else if pattern.value & bit_mask == 0 {
+                zero.push(index);
+            } else {
+                one.push(index);
+            }
+        }
+        DispatchNode::Split {
+            bit,
+            zero: Box::new(Self::build_node(zero, patterns, used_bits | bit_mask)),
+            one: Box::new(Self::build_node(one, patterns, used_bits | bit_mask)),
+        }
+    }
+
+    /// Returns the format whose fixed bits match the opcode, if there is one.
+    ///
+    /// When formats overlap, the one registered first is selected, as with
+    /// trying every format in turn.
+    fn find(&self, opcode: u32) -> Option<&(InstructionFormat, Decoder)> {
+        let mut node = &self.root;
+        loop {
+            match node {
+                DispatchNode::Split { bit, zero, one } => {
+                    node = if opcode & (1 << bit) == 0 { zero } else { one };
+                }
+                DispatchNode::Leaf(candidates) => {
+                    return candidates
+                        .iter()
+                        .map(|index| &self.patterns[*index])
+                        .find(|pattern| pattern.matches(opcode))
+                        .map(|pattern| &self.formats[pattern.format_index]);
+                }
+            }
+        }
+    }
+
+    /// Decodes the input using the single format selected for its opcode.
+    ///
+    /// Returns [None] if no registered format matches the opcode.
+    pub fn decode(&self, input: &InstructionInput) -> Option<Result<Box<dyn C6000Instruction>>> {
+        let (format, decoder) = self.find(input.opcode)?;
+        Some(
+            parse(input.opcode, &format.parsing_instructions)
+                .and_then(|parsed_variables| decoder(format.name, input, &parsed_variables)),
+        )
+    }
+
+    /// Returns the names of every pair of registered formats which accept
+    /// at least one common opcode.
+    pub fn overlaps(&self) -> Vec<(&'static str, &'static str)> {
+        let mut overlaps = Vec::new();
+        for (i, first) in self.patterns.iter().enumerate() {
+            for second in &self.patterns[i + 1..] {
+                if first.format_index == second.format_index {
+                    continue;
+                }
+                let common_mask = first.mask & second.mask;
+                if (first.value ^ second.value) & common_mask == 0 {
+                    overlaps.push((
+                        self.formats[first.format_index].0.name,
+                        self.formats[second.format_index].0.name,
+                    ));
+                }
+            }
+        }
+        overlaps
+    }
+}
+
+fn register<T: C6000Instruction + 'static>(formats: &mut Vec<(InstructionFormat, Decoder)>) {
+    for format in T::formats() {
+        formats.push((format, decode_as::<T>));
+    }
+}
+
+fn register_compact<T: C6000Instruction + 'static>(
+    formats: &mut Vec<(InstructionFormat, Decoder)>,
+) {
+    for format in T::compact_formats() {
+        formats.push((format, decode_compact_as::<T>));
+    }
+}
+
+static INSTRUCTION_TABLE: LazyLock<DispatchTable> = LazyLock::new(|| {
+    let mut formats = Vec::new();
+    register::<MoveConstantInstruction>(&mut formats);
+    register::<MoveRegisterInstruction>(&mut formats);
+    register::<BranchInstruction>(&mut formats);
+    register::<MemoryInstruction>(&mut formats);
+    register::<NOPInstruction>(&mut formats);
+    register::<ADDKPCInstruction>(&mut formats);
+    DispatchTable::new(formats)
+});
+
+static COMPACT_INSTRUCTION_TABLE: LazyLock<DispatchTable> = LazyLock::new(|| {
+    let mut formats = Vec::new();
+    register_compact::<MoveConstantInstruction>(&mut formats);
+    register_compact::<MoveRegisterInstruction>(&mut formats);
+    register_compact::<BranchInstruction>(&mut formats);
+    register_compact::<MemoryInstruction>(&mut formats);
+    register_compact::<NOPInstruction>(&mut formats);
+    DispatchTable::new(formats)
+});
+
+/// Dispatch table of every 32-bit instruction format.
+pub fn instruction_table() -> &'static DispatchTable {
+    &INSTRUCTION_TABLE
+}
+
+/// Dispatch table of every compact (16-bit) instruction format.
+pub fn compact_instruction_table() -> &'static DispatchTable {
+    &COMPACT_INSTRUCTION_TABLE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_do_not_overlap() {
+        assert_eq!(instruction_table().overlaps(), vec![]);
+    }
+
+    #[test]
+    fn compact_formats_do_not_overlap() {
+        assert_eq!(compact_instruction_table().overlaps(), vec![]);
+    }
+}
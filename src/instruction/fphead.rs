@@ -1,7 +1,8 @@
-use std::io::{Error, ErrorKind, Result};
-
+use crate::error::{DecodeError, Result};
 use crate::instruction::{
     C6000Instruction, DataSize, InstructionData,
+    mnemonic::Mnemonic,
+    operand::Operand,
     parser::{ParsedVariable, ParsingInstruction, parse},
 };
 
@@ -70,25 +71,30 @@ impl C6000Instruction for CompactInstructionHeader {
                 value: 0b1110,
             },
         ];
-        let parsed_variables = parse(input.opcode, &format).map_err(|e| {
-            Error::new(
-                ErrorKind::InvalidInput,
-                format!("Not a compact instruction header: {e}"),
-            )
+        let parsed_variables = parse(input.opcode, &format).map_err(|e| match e {
+            DecodeError::OpcodeMismatch { opcode, reason } => DecodeError::OpcodeMismatch {
+                opcode,
+                reason: format!("Not a compact instruction header ({reason})"),
+            },
+            e => e,
         })?;
 
         let layout = {
             let layout_vec =
                 ParsedVariable::try_get(&parsed_variables, "layout")?.get_bool_vec()?;
             let Some(layout_ref) = layout_vec.first_chunk::<7>() else {
-                return Err(Error::other("Layout doesn't have 7 elements"));
+                return Err(DecodeError::VariableType {
+                    expected: "BoolVec with 7 elements",
+                });
             };
             *layout_ref
         };
         let compact_p_bits = {
             let layout_vec = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool_vec()?;
             let Some(layout_ref) = layout_vec.first_chunk::<14>() else {
-                return Err(Error::other("P-bits don't have 14 elements"));
+                return Err(DecodeError::VariableType {
+                    expected: "BoolVec with 14 elements",
+                });
             };
             *layout_ref
         };
@@ -97,7 +103,7 @@ impl C6000Instruction for CompactInstructionHeader {
         let data_sizes_1 = ParsedVariable::try_get(&parsed_variables, "DSZ_1")?.get_u8()?;
         let data_sizes_2 = ParsedVariable::try_get(&parsed_variables, "DSZ_2")?.get_bool()?;
         let primary_data_size = {
-            if data_sizes_2 == true {
+            if data_sizes_2 {
                 DataSize::DoubleWord
             } else {
                 DataSize::Word
@@ -141,45 +147,32 @@ impl C6000Instruction for CompactInstructionHeader {
         })
     }
 
+    fn mnemonic(&self) -> Mnemonic {
+        Mnemonic::FPHEAD
+    }
     fn instruction(&self) -> String {
-        String::from(".fphead")
+        self.instruction_clean()
     }
-    fn operands(&self) -> String {
-        let mut return_str = String::new();
-        if self.loads_protected {
-            return_str += "p";
-        } else {
-            return_str += "n";
-        }
-        return_str += ", ";
-        if self.register_set {
-            return_str += "h";
-        } else {
-            return_str += "l";
-        }
-        return_str += ", ";
-        return_str += (self.primary_data_size.to_short_string() + ", ").as_str();
-        return_str += (self.secondary_data_size.to_short_string() + ", ").as_str();
-        if self.decode_compact_branches {
-            return_str += "br";
-        } else {
-            return_str += "nobr";
-        }
-        return_str += ", ";
-        if self.saturate {
-            return_str += "sat";
-        } else {
-            return_str += "nosat";
-        }
-        return_str += ", ";
-        for i in (0..7).rev() {
-            if self.layout[i] {
-                return_str += "1";
+    fn operand_list(&self) -> Vec<Operand> {
+        let layout = (0..7)
+            .filter(|i| self.layout[*i])
+            .fold(0, |value, i| value | 1 << i);
+        vec![
+            Operand::Keyword(if self.loads_protected { "p" } else { "n" }),
+            Operand::Keyword(if self.register_set { "h" } else { "l" }),
+            Operand::Keyword(self.primary_data_size.short_name()),
+            Operand::Keyword(self.secondary_data_size.short_name()),
+            Operand::Keyword(if self.decode_compact_branches {
+                "br"
             } else {
-                return_str += "0";
-            }
-        }
-        return_str
+                "nobr"
+            }),
+            Operand::Keyword(if self.saturate { "sat" } else { "nosat" }),
+            Operand::Bits {
+                value: layout,
+                size: 7,
+            },
+        ]
     }
     fn instruction_data(&self) -> &InstructionData {
         &self.instruction_data
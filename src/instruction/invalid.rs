@@ -1,29 +1,51 @@
-use crate::instruction::{C6000Instruction, InstructionData};
+use crate::error::{DecodeError, Result};
+use crate::instruction::{C6000Instruction, InstructionData, mnemonic::Mnemonic};
 
 pub struct InvalidInstruction {
     instruction_data: InstructionData,
+    error: Option<DecodeError>,
+}
+
+impl InvalidInstruction {
+    /// Sets the error which prevented the opcode from being decoded.
+    pub fn with_error(mut self, error: DecodeError) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    /// Returns the error which prevented the opcode from being decoded, if known.
+    pub fn error(&self) -> Option<&DecodeError> {
+        self.error.as_ref()
+    }
 }
 
 impl C6000Instruction for InvalidInstruction {
-    fn new(input: &super::InstructionInput) -> std::io::Result<Self> {
+    fn new(input: &super::InstructionInput) -> Result<Self> {
         Ok(InvalidInstruction {
             instruction_data: InstructionData {
                 opcode: input.opcode,
+                p_bit: input.opcode & 1 == 1,
                 ..Default::default()
             },
+            error: None,
         })
     }
 
-    fn new_compact(input: &super::InstructionInput) -> std::io::Result<Self> {
+    fn new_compact(input: &super::InstructionInput) -> Result<Self> {
         Ok(InvalidInstruction {
             instruction_data: InstructionData {
                 opcode: input.opcode,
                 compact: true,
                 ..Default::default()
             },
+            error: None,
         })
     }
 
+    fn mnemonic(&self) -> Mnemonic {
+        Mnemonic::INVALID
+    }
+
     fn instruction(&self) -> String {
         if self.is_compact() {
             String::from("INVALID COMPACT INSTRUCTION")
@@ -40,3 +62,48 @@ impl C6000Instruction for InvalidInstruction {
         &mut self.instruction_data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instruction::InstructionInput, read_instruction};
+
+    fn decode_error(opcode: u32) -> Option<DecodeError> {
+        let instruction = read_instruction(InstructionInput {
+            opcode,
+            fphead: None,
+            pce1_address: 0,
+        })
+        .unwrap();
+        instruction
+            .as_any()
+            .downcast_ref::<InvalidInstruction>()?
+            .error()
+            .cloned()
+    }
+
+    #[test]
+    fn keeps_error_of_matching_format() {
+        assert_eq!(
+            decode_error(0x023C24E4),
+            Some(DecodeError::ReservedField {
+                opcode: 0x023C24E4,
+                field: String::from("mode"),
+                value: 0b0010,
+            })
+        );
+    }
+
+    #[test]
+    fn reports_unknown_opcodes() {
+        assert_eq!(
+            decode_error(0x00000002),
+            Some(DecodeError::UnknownOpcode { opcode: 0x00000002 })
+        );
+    }
+
+    #[test]
+    fn decoded_instructions_are_not_invalid() {
+        assert_eq!(decode_error(0x023C32E4), None);
+    }
+}
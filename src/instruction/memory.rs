@@ -1,12 +1,15 @@
 use std::{
+    collections::{HashMap, HashSet},
     fmt::Display,
-    io::{Error, ErrorKind, Result},
 };
 
+use crate::error::{DecodeError, Result};
 use crate::instruction::{
-    C6000Instruction, DataSize, InstructionData,
-    parser::{ParsedVariable, ParsingInstruction, parse},
-    register::Register,
+    C6000Instruction, DataSize, InstructionData, InstructionInput, Unit,
+    mnemonic::Mnemonic,
+    operand::Operand,
+    parser::{InstructionFormat, ParsedVariable, ParsingInstruction},
+    register::{Register, RegisterFile, register_set},
 };
 
 #[derive(PartialEq, Eq)]
@@ -15,6 +18,7 @@ pub enum MemoryInstructionType {
     Store,
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum AddressGeneratorMode {
     NegativeR(Register),
     PositiveR(Register),
@@ -43,6 +47,17 @@ impl AddressGeneratorMode {
         }
     }
 
+    /// Determines if the address is written back to the base register.
+    pub fn writes_back(&self) -> bool {
+        !matches!(
+            self,
+            AddressGeneratorMode::NegativeR(_)
+                | AddressGeneratorMode::PositiveR(_)
+                | AddressGeneratorMode::Negative(_)
+                | AddressGeneratorMode::Positive(_)
+        )
+    }
+
     pub fn get_constant(&self) -> Option<u32> {
         match self {
             AddressGeneratorMode::Negative(cst)
@@ -86,273 +101,89 @@ pub struct MemoryInstruction {
 }
 
 impl C6000Instruction for MemoryInstruction {
-    fn new(input: &super::InstructionInput) -> Result<Self> {
-        let formats = [
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match { size: 2, value: 1 },
-                ParsingInstruction::Unsigned {
-                    size: 3,
-                    name: String::from("op"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("y"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("op2"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 4,
-                    name: String::from("mode"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 5,
-                    name: String::from("offset"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 5,
-                    name: String::from("baseR"),
-                },
-                ParsingInstruction::Register {
-                    size: 5,
-                    name: String::from("register"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("creg"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match {
-                    size: 2,
-                    value: 0b11,
-                },
-                ParsingInstruction::Unsigned {
-                    size: 3,
-                    name: String::from("op"),
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("y"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 15,
-                    name: String::from("cst"),
-                },
-                ParsingInstruction::Register {
-                    size: 5,
-                    name: String::from("register"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("creg"),
-                },
-            ],
-        ];
-
-        for format in formats {
-            let Ok(parsed_variables) = parse(input.opcode, &format) else {
-                continue;
-            };
-            let p_bit = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool()?;
-
-            let op2 = {
-                if let Ok(var) = ParsedVariable::try_get(&parsed_variables, "op2") {
-                    var.get_bool()?
-                } else {
-                    false
-                }
-            };
-            let (instruction_type, data_size) =
-                match ParsedVariable::try_get(&parsed_variables, "op")?.get_u8()? {
-                    0b111 if !op2 => (MemoryInstructionType::Store, DataSize::Word),
-                    0b011 if !op2 => (MemoryInstructionType::Store, DataSize::Byte),
-                    0b100 if op2 => (MemoryInstructionType::Store, DataSize::DoubleWord),
-                    0b101 if !op2 => (MemoryInstructionType::Store, DataSize::HalfWord),
-                    0b111 if op2 => (MemoryInstructionType::Store, DataSize::NonAlignedDoubleWord),
-                    0b101 if op2 => (MemoryInstructionType::Store, DataSize::NonAlignedWord),
-                    0b010 if !op2 => (MemoryInstructionType::Load, DataSize::Byte),
-                    0b001 if !op2 => (MemoryInstructionType::Load, DataSize::ByteUnsigned),
-                    0b110 if op2 => (MemoryInstructionType::Load, DataSize::DoubleWord),
-                    0b100 if !op2 => (MemoryInstructionType::Load, DataSize::HalfWord),
-                    0b000 if !op2 => (MemoryInstructionType::Load, DataSize::HalfWordUnsigned),
-                    0b010 if op2 => (MemoryInstructionType::Load, DataSize::NonAlignedDoubleWord),
-                    0b011 if op2 => (MemoryInstructionType::Load, DataSize::NonAlignedWord),
-                    0b110 if !op2 => (MemoryInstructionType::Load, DataSize::Word),
-                    _ => {
-                        return Err(Error::new(
-                            ErrorKind::InvalidInput,
-                            "Invalid memory instruction opcode",
-                        ));
-                    }
-                };
-
-            let side = ParsedVariable::try_get(&parsed_variables, "y")?.get_bool()?;
-            let mode = {
-                if let Ok(var) = ParsedVariable::try_get(&parsed_variables, "mode") {
-                    let offset = ParsedVariable::try_get(&parsed_variables, "offset")?.get_u8()?;
-                    let res = match var.get_u8()? {
-                        0b0000 => AddressGeneratorMode::Negative(offset as u32),
-                        0b0001 => AddressGeneratorMode::Positive(offset as u32),
-                        0b1000 => AddressGeneratorMode::Predecrement(offset as u32),
-                        0b1001 => AddressGeneratorMode::Preincrement(offset as u32),
-                        0b1010 => AddressGeneratorMode::Postdecrement(offset as u32),
-                        0b1011 => AddressGeneratorMode::Postincrement(offset as u32),
-                        0b0100 => AddressGeneratorMode::NegativeR(Register::from(offset, side)),
-                        0b0101 => AddressGeneratorMode::PositiveR(Register::from(offset, side)),
-                        0b1100 => AddressGeneratorMode::PredecrementR(Register::from(offset, side)),
-                        0b1101 => AddressGeneratorMode::PreincrementR(Register::from(offset, side)),
-                        0b1110 => {
-                            AddressGeneratorMode::PostdecrementR(Register::from(offset, side))
-                        }
-                        0b1111 => {
-                            AddressGeneratorMode::PostincrementR(Register::from(offset, side))
-                        }
-                        _ => {
-                            return Err(Error::new(
-                                ErrorKind::InvalidInput,
-                                "Invalid memory instruction mode",
-                            ));
-                        }
-                    };
-                    res
-                } else {
-                    let cst = ParsedVariable::try_get(&parsed_variables, "cst")?.get_u32()?;
-                    AddressGeneratorMode::Positive(cst)
-                }
-            };
-
-            let base_register = {
-                if let Ok(var) = ParsedVariable::try_get(&parsed_variables, "baseR") {
-                    Register::from(var.get_u8()?, side)
-                } else {
-                    if side {
-                        Register::B(15)
-                    } else {
-                        Register::B(14)
-                    }
-                }
-            };
-            let register =
-                ParsedVariable::try_get(&parsed_variables, "register")?.get_register()?;
-
-            return Ok(Self {
-                instruction_type,
-                data_size,
-                mode,
-                base_register,
-                side,
-                register,
-                instruction_data: InstructionData {
-                    opcode: input.opcode,
-                    compact: false,
-                    p_bit,
-                    ..Default::default()
-                },
-            });
-        }
-
-        Err(Error::new(
-            ErrorKind::InvalidInput,
-            "Not a memory load/store instruction",
-        ))
-    }
-
-    fn new_compact(input: &super::InstructionInput) -> Result<Self> {
-        let Some(fphead) = &input.fphead else {
-            return Err(Error::new(ErrorKind::InvalidInput, "No fphead"));
-        };
-
-        let formats = [
-            (
-                "Doff4",
+    fn formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "Dmem",
                 vec![
                     ParsingInstruction::Bit {
-                        name: String::from("s"),
-                    },
-                    ParsingInstruction::Match {
-                        size: 2,
-                        value: 0b10,
+                        name: String::from("p"),
                     },
                     ParsingInstruction::Bit {
-                        name: String::from("load"),
+                        name: String::from("s"),
                     },
+                    ParsingInstruction::Match { size: 2, value: 1 },
                     ParsingInstruction::Unsigned {
-                        name: String::from("register"),
                         size: 3,
+                        name: String::from("op"),
                     },
-                    ParsingInstruction::Unsigned {
-                        name: String::from("ptr"),
-                        size: 2,
+                    ParsingInstruction::Bit {
+                        name: String::from("y"),
                     },
                     ParsingInstruction::Bit {
-                        name: String::from("sz"),
+                        name: String::from("op2"),
                     },
-                    ParsingInstruction::Match { size: 1, value: 0 },
                     ParsingInstruction::Unsigned {
-                        name: String::from("cst3"),
-                        size: 1,
+                        size: 4,
+                        name: String::from("mode"),
                     },
-                    ParsingInstruction::Bit {
-                        name: String::from("t"),
+                    ParsingInstruction::Unsigned {
+                        size: 5,
+                        name: String::from("offset"),
                     },
                     ParsingInstruction::Unsigned {
-                        name: String::from("cst20"),
-                        size: 3,
+                        size: 5,
+                        name: String::from("baseR"),
+                    },
+                    ParsingInstruction::Register {
+                        size: 5,
+                        name: String::from("register"),
+                    },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("creg"),
                     },
                 ],
             ),
-            (
-                "Doff4DW",
+            InstructionFormat::new(
+                "Dmem15",
                 vec![
                     ParsingInstruction::Bit {
-                        name: String::from("s"),
-                    },
-                    ParsingInstruction::Match {
-                        size: 2,
-                        value: 0b10,
-                    },
-                    ParsingInstruction::Bit {
-                        name: String::from("load"),
+                        name: String::from("p"),
                     },
                     ParsingInstruction::Bit {
-                        name: String::from("na"),
+                        name: String::from("s"),
                     },
-                    ParsingInstruction::Unsigned {
-                        name: String::from("register"),
+                    ParsingInstruction::Match {
                         size: 2,
+                        value: 0b11,
                     },
                     ParsingInstruction::Unsigned {
-                        name: String::from("ptr"),
-                        size: 2,
+                        size: 3,
+                        name: String::from("op"),
                     },
                     ParsingInstruction::Bit {
-                        name: String::from("sz"),
+                        name: String::from("y"),
                     },
-                    ParsingInstruction::Match { size: 1, value: 0 },
                     ParsingInstruction::Unsigned {
-                        name: String::from("cst3"),
-                        size: 1,
+                        size: 15,
+                        name: String::from("cst"),
                     },
-                    ParsingInstruction::Bit {
-                        name: String::from("t"),
+                    ParsingInstruction::Register {
+                        size: 5,
+                        name: String::from("register"),
                     },
-                    ParsingInstruction::Unsigned {
-                        name: String::from("cst20"),
-                        size: 3,
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("creg"),
                     },
                 ],
             ),
-            (
-                "Dind",
+        ]
+    }
+
+    fn compact_formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "Doff4",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("s"),
@@ -375,18 +206,22 @@ impl C6000Instruction for MemoryInstruction {
                     ParsingInstruction::Bit {
                         name: String::from("sz"),
                     },
-                    ParsingInstruction::Match { size: 2, value: 1 },
+                    ParsingInstruction::Match { size: 1, value: 0 },
+                    ParsingInstruction::Unsigned {
+                        name: String::from("cst3"),
+                        size: 1,
+                    },
                     ParsingInstruction::Bit {
                         name: String::from("t"),
                     },
-                    ParsingInstruction::Register {
-                        name: String::from("src"),
+                    ParsingInstruction::Unsigned {
+                        name: String::from("cst20"),
                         size: 3,
                     },
                 ],
             ),
-            (
-                "DindDW",
+            InstructionFormat::new(
+                "Dind",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("s"),
@@ -398,12 +233,9 @@ impl C6000Instruction for MemoryInstruction {
                     ParsingInstruction::Bit {
                         name: String::from("load"),
                     },
-                    ParsingInstruction::Bit {
-                        name: String::from("na"),
-                    },
                     ParsingInstruction::Unsigned {
                         name: String::from("register"),
-                        size: 2,
+                        size: 3,
                     },
                     ParsingInstruction::Unsigned {
                         name: String::from("ptr"),
@@ -422,7 +254,7 @@ impl C6000Instruction for MemoryInstruction {
                     },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "Dincdec",
                 vec![
                     ParsingInstruction::Bit {
@@ -463,51 +295,7 @@ impl C6000Instruction for MemoryInstruction {
                     ParsingInstruction::Match { size: 1, value: 0 },
                 ],
             ),
-            (
-                "DincdecDW",
-                vec![
-                    ParsingInstruction::Bit {
-                        name: String::from("s"),
-                    },
-                    ParsingInstruction::Match {
-                        size: 2,
-                        value: 0b10,
-                    },
-                    ParsingInstruction::Bit {
-                        name: String::from("load"),
-                    },
-                    ParsingInstruction::Bit {
-                        name: String::from("na"),
-                    },
-                    ParsingInstruction::Unsigned {
-                        name: String::from("register"),
-                        size: 2,
-                    },
-                    ParsingInstruction::Unsigned {
-                        name: String::from("ptr"),
-                        size: 2,
-                    },
-                    ParsingInstruction::Bit {
-                        name: String::from("sz"),
-                    },
-                    ParsingInstruction::Match {
-                        size: 2,
-                        value: 0b11,
-                    },
-                    ParsingInstruction::Bit {
-                        name: String::from("t"),
-                    },
-                    ParsingInstruction::Unsigned {
-                        name: String::from("cst0"),
-                        size: 1,
-                    },
-                    ParsingInstruction::Bit {
-                        name: String::from("dec"),
-                    },
-                    ParsingInstruction::Match { size: 1, value: 0 },
-                ],
-            ),
-            (
+            InstructionFormat::new(
                 "Dstk",
                 vec![
                     ParsingInstruction::BitMatch {
@@ -543,7 +331,7 @@ impl C6000Instruction for MemoryInstruction {
                     ParsingInstruction::Match { size: 1, value: 1 },
                 ],
             ),
-            (
+            InstructionFormat::new(
                 "Dpp",
                 vec![
                     ParsingInstruction::BitMatch {
@@ -574,141 +362,252 @@ impl C6000Instruction for MemoryInstruction {
                     },
                 ],
             ),
-        ];
-        for (name, format) in formats {
-            let Ok(parsed_variables) = parse(input.opcode, format.as_slice()) else {
-                continue;
-            };
-            let side = ParsedVariable::try_get(&parsed_variables, "s")?.get_bool()?;
-            let t = ParsedVariable::try_get(&parsed_variables, "t")?.get_bool()?;
-
-            let instruction_type = {
-                if ParsedVariable::try_get(&parsed_variables, "load")?.get_bool()? {
-                    MemoryInstructionType::Load
-                } else {
-                    MemoryInstructionType::Store
+        ]
+    }
+
+    fn from_format(
+        _format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+
+        let op2 = {
+            if let Ok(var) = ParsedVariable::try_get(parsed_variables, "op2") {
+                var.get_bool()?
+            } else {
+                false
+            }
+        };
+        let op = ParsedVariable::try_get(parsed_variables, "op")?.get_u8()?;
+        let (instruction_type, data_size) = match op {
+            0b111 if !op2 => (MemoryInstructionType::Store, DataSize::Word),
+            0b011 if !op2 => (MemoryInstructionType::Store, DataSize::Byte),
+            0b100 if op2 => (MemoryInstructionType::Store, DataSize::DoubleWord),
+            0b101 if !op2 => (MemoryInstructionType::Store, DataSize::HalfWord),
+            0b111 if op2 => (MemoryInstructionType::Store, DataSize::NonAlignedDoubleWord),
+            0b101 if op2 => (MemoryInstructionType::Store, DataSize::NonAlignedWord),
+            0b010 if !op2 => (MemoryInstructionType::Load, DataSize::Byte),
+            0b001 if !op2 => (MemoryInstructionType::Load, DataSize::ByteUnsigned),
+            0b110 if op2 => (MemoryInstructionType::Load, DataSize::DoubleWord),
+            0b100 if !op2 => (MemoryInstructionType::Load, DataSize::HalfWord),
+            0b000 if !op2 => (MemoryInstructionType::Load, DataSize::HalfWordUnsigned),
+            0b010 if op2 => (MemoryInstructionType::Load, DataSize::NonAlignedDoubleWord),
+            0b011 if op2 => (MemoryInstructionType::Load, DataSize::NonAlignedWord),
+            0b110 if !op2 => (MemoryInstructionType::Load, DataSize::Word),
+            _ => {
+                return Err(DecodeError::ReservedField {
+                    opcode: input.opcode,
+                    field: String::from("op"),
+                    value: op as u32 | (op2 as u32) << 3,
+                });
+            }
+        };
+
+        let side = ParsedVariable::try_get(parsed_variables, "y")?.get_bool()?;
+        let mode = {
+            if let Ok(var) = ParsedVariable::try_get(parsed_variables, "mode") {
+                let offset = ParsedVariable::try_get(parsed_variables, "offset")?.get_u8()?;
+                let mode = var.get_u8()?;
+                match mode {
+                    0b0000 => AddressGeneratorMode::Negative(offset as u32),
+                    0b0001 => AddressGeneratorMode::Positive(offset as u32),
+                    0b1000 => AddressGeneratorMode::Predecrement(offset as u32),
+                    0b1001 => AddressGeneratorMode::Preincrement(offset as u32),
+                    0b1010 => AddressGeneratorMode::Postdecrement(offset as u32),
+                    0b1011 => AddressGeneratorMode::Postincrement(offset as u32),
+                    0b0100 => AddressGeneratorMode::NegativeR(Register::from(offset, side)),
+                    0b0101 => AddressGeneratorMode::PositiveR(Register::from(offset, side)),
+                    0b1100 => AddressGeneratorMode::PredecrementR(Register::from(offset, side)),
+                    0b1101 => AddressGeneratorMode::PreincrementR(Register::from(offset, side)),
+                    0b1110 => AddressGeneratorMode::PostdecrementR(Register::from(offset, side)),
+                    0b1111 => AddressGeneratorMode::PostincrementR(Register::from(offset, side)),
+                    _ => {
+                        return Err(DecodeError::ReservedField {
+                            opcode: input.opcode,
+                            field: String::from("mode"),
+                            value: mode as u32,
+                        });
+                    }
                 }
-            };
+            } else {
+                let cst = ParsedVariable::try_get(parsed_variables, "cst")?.get_u32()?;
+                AddressGeneratorMode::Positive(cst)
+            }
+        };
+
+        let base_register = {
+            if let Ok(var) = ParsedVariable::try_get(parsed_variables, "baseR") {
+                Register::from(var.get_u8()?, side)
+            } else if side {
+                Register::B(15)
+            } else {
+                Register::B(14)
+            }
+        };
+        let register = ParsedVariable::try_get(parsed_variables, "register")?.get_register()?;
+
+        Ok(Self {
+            instruction_type,
+            data_size,
+            mode,
+            base_register,
+            side,
+            register,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                compact: false,
+                p_bit,
+                ..Default::default()
+            },
+        })
+    }
 
-            let base_register = {
-                if name == "Dstk" || name == "Dpp" {
-                    Register::B(15)
+    fn from_compact_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let Some(fphead) = &input.fphead else {
+            return Err(DecodeError::MissingHeader {
+                opcode: input.opcode,
+            });
+        };
+
+        let side = ParsedVariable::try_get(parsed_variables, "s")?.get_bool()?;
+        let t = ParsedVariable::try_get(parsed_variables, "t")?.get_bool()?;
+        let mut reg_value = ParsedVariable::try_get(parsed_variables, "register")?.get_u8()?;
+
+        let instruction_type = {
+            if ParsedVariable::try_get(parsed_variables, "load")?.get_bool()? {
+                MemoryInstructionType::Load
+            } else {
+                MemoryInstructionType::Store
+            }
+        };
+
+        let base_register = {
+            if format == "Dstk" || format == "Dpp" {
+                Register::B(15)
+            } else {
+                let ptr = ParsedVariable::try_get(parsed_variables, "ptr")?.get_u8()?;
+                Register::from(ptr + 4, side)
+            }
+        };
+
+        let mode = match format {
+            "Doff4" => {
+                let cst20 = ParsedVariable::try_get(parsed_variables, "cst20")?.get_u8()?;
+                let cst3 = ParsedVariable::try_get(parsed_variables, "cst3")?.get_u8()?;
+                let cst = cst20 + (cst3 << 3);
+                AddressGeneratorMode::Positive(cst as u32)
+            }
+            "Dind" => {
+                let src = ParsedVariable::try_get(parsed_variables, "src")?.get_register()?;
+                AddressGeneratorMode::PositiveR(src)
+            }
+            "Dincdec" => {
+                let cst = ParsedVariable::try_get(parsed_variables, "cst0")?.get_u8()? + 1;
+                let dec = ParsedVariable::try_get(parsed_variables, "dec")?.get_bool()?;
+                if dec {
+                    AddressGeneratorMode::Predecrement(cst as u32)
                 } else {
-                    let ptr = ParsedVariable::try_get(&parsed_variables, "ptr")?.get_u8()?;
-                    Register::from(ptr + 4, side)
+                    AddressGeneratorMode::Postincrement(cst as u32)
                 }
-            };
-
-            let mode = {
-                if name.starts_with("Doff4") {
-                    let cst20 = ParsedVariable::try_get(&parsed_variables, "cst20")?.get_u8()?;
-                    let cst3 = ParsedVariable::try_get(&parsed_variables, "cst3")?.get_u8()?;
-                    let cst = cst20 + (cst3 << 3);
-                    AddressGeneratorMode::Positive(cst as u32)
-                } else if name.starts_with("Dind") {
-                    let src = ParsedVariable::try_get(&parsed_variables, "src")?.get_register()?;
-                    AddressGeneratorMode::PositiveR(src)
-                } else if name.starts_with("Dincdec") {
-                    let cst = ParsedVariable::try_get(&parsed_variables, "cst0")?.get_u8()? + 1;
-                    let dec = ParsedVariable::try_get(&parsed_variables, "dec")?.get_bool()?;
-                    if dec {
-                        AddressGeneratorMode::Predecrement(cst as u32)
-                    } else {
-                        AddressGeneratorMode::Postincrement(cst as u32)
-                    }
-                } else if name == "Dstk" {
-                    let cst10 = ParsedVariable::try_get(&parsed_variables, "cst10")?.get_u8()?;
-                    let cst42 = ParsedVariable::try_get(&parsed_variables, "cst42")?.get_u8()?;
-                    let cst = cst10 + (cst42 << 2);
-                    AddressGeneratorMode::Positive(cst as u32)
-                } else if name == "Dpp" {
-                    let cst = ParsedVariable::try_get(&parsed_variables, "cst0")?.get_u8()? + 1;
-                    if instruction_type == MemoryInstructionType::Load {
-                        AddressGeneratorMode::Preincrement(cst as u32)
-                    } else {
-                        AddressGeneratorMode::Postdecrement(cst as u32)
-                    }
+            }
+            "Dstk" => {
+                let cst10 = ParsedVariable::try_get(parsed_variables, "cst10")?.get_u8()?;
+                let cst42 = ParsedVariable::try_get(parsed_variables, "cst42")?.get_u8()?;
+                let cst = cst10 + (cst42 << 2);
+                AddressGeneratorMode::Positive(cst as u32)
+            }
+            "Dpp" => {
+                let cst = ParsedVariable::try_get(parsed_variables, "cst0")?.get_u8()? + 1;
+                if instruction_type == MemoryInstructionType::Load {
+                    AddressGeneratorMode::Preincrement(cst as u32)
                 } else {
-                    AddressGeneratorMode::Positive(0)
+                    AddressGeneratorMode::Postdecrement(cst as u32)
                 }
-            };
+            }
+            _ => AddressGeneratorMode::Positive(0),
+        };
 
-            let data_size = {
-                if name == "Dstk" {
+        let data_size = {
+            if format == "Dstk" {
+                DataSize::Word
+            } else if format == "Dpp" {
+                let dw = ParsedVariable::try_get(parsed_variables, "dw")?.get_bool()?;
+                if dw {
+                    DataSize::DoubleWord
+                } else {
                     DataSize::Word
-                } else if name == "Dpp" {
-                    let dw = ParsedVariable::try_get(&parsed_variables, "dw")?.get_bool()?;
-                    if dw {
-                        DataSize::DoubleWord
+                }
+            } else {
+                let sz = ParsedVariable::try_get(parsed_variables, "sz")?.get_bool()?;
+                if sz {
+                    fphead.secondary_data_size
+                } else if fphead.primary_data_size == DataSize::DoubleWord {
+                    // Doubleword accesses use the lowest register bit as the
+                    // non-aligned (na) flag.
+                    let na = reg_value & 1 == 1;
+                    reg_value >>= 1;
+                    if na {
+                        DataSize::NonAlignedDoubleWord
                     } else {
-                        DataSize::Word
+                        DataSize::DoubleWord
                     }
                 } else {
-                    let sz = ParsedVariable::try_get(&parsed_variables, "sz")?.get_bool()?;
-                    if sz {
-                        if name.ends_with("DW") {
-                            continue;
-                        }
-                        fphead.secondary_data_size
-                    } else {
-                        if name.ends_with("DW") {
-                            if fphead.primary_data_size != DataSize::DoubleWord {
-                                continue;
-                            }
-                            let na =
-                                ParsedVariable::try_get(&parsed_variables, "na")?.get_bool()?;
-                            if na {
-                                DataSize::NonAlignedDoubleWord
-                            } else {
-                                DataSize::DoubleWord
-                            }
-                        } else {
-                            if fphead.primary_data_size != DataSize::Word {
-                                continue;
-                            }
-                            DataSize::Word
-                        }
-                    }
+                    DataSize::Word
                 }
-            };
+            }
+        };
 
-            let register = {
-                let reg_value = ParsedVariable::try_get(&parsed_variables, "register")?.get_u8()?;
-                if data_size == DataSize::DoubleWord || data_size == DataSize::NonAlignedDoubleWord
-                {
-                    Register::from_pair(reg_value, t)
-                } else {
-                    Register::from(reg_value, t)
-                }
-            };
+        let register = {
+            if data_size == DataSize::DoubleWord || data_size == DataSize::NonAlignedDoubleWord {
+                Register::from_pair(reg_value, t)
+            } else {
+                Register::from(reg_value, t)
+            }
+        };
 
-            return Ok(Self {
-                instruction_data: InstructionData {
-                    opcode: input.opcode,
-                    compact: true,
-                    ..Default::default()
-                },
-                instruction_type,
-                data_size,
-                base_register,
-                mode,
-                side,
-                register,
-            });
-        }
-        Err(Error::new(
-            ErrorKind::InvalidInput,
-            "Not a memory load/store instruction",
-        ))
+        Ok(Self {
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                compact: true,
+                ..Default::default()
+            },
+            instruction_type,
+            data_size,
+            base_register,
+            mode,
+            side,
+            register,
+        })
     }
 
-    fn instruction_clean(&self) -> String {
-        let prefix = if self.instruction_type == MemoryInstructionType::Load {
-            String::from("LD")
-        } else {
-            String::from("ST")
-        };
-        format!("{prefix}{}", self.data_size.to_short_string())
+    fn mnemonic(&self) -> Mnemonic {
+        match self.instruction_type {
+            MemoryInstructionType::Load => match self.data_size {
+                DataSize::Byte => Mnemonic::LDB,
+                DataSize::ByteUnsigned => Mnemonic::LDBU,
+                DataSize::HalfWord => Mnemonic::LDH,
+                DataSize::HalfWordUnsigned => Mnemonic::LDHU,
+                DataSize::Word => Mnemonic::LDW,
+                DataSize::NonAlignedWord => Mnemonic::LDNW,
+                DataSize::DoubleWord => Mnemonic::LDDW,
+                DataSize::NonAlignedDoubleWord => Mnemonic::LDNDW,
+            },
+            // Stores don't extend the value, so the signedness of the
+            // data size (taken from the fphead for compact stores) is ignored
+            MemoryInstructionType::Store => match self.data_size {
+                DataSize::Byte | DataSize::ByteUnsigned => Mnemonic::STB,
+                DataSize::HalfWord | DataSize::HalfWordUnsigned => Mnemonic::STH,
+                DataSize::Word => Mnemonic::STW,
+                DataSize::NonAlignedWord => Mnemonic::STNW,
+                DataSize::DoubleWord => Mnemonic::STDW,
+                DataSize::NonAlignedDoubleWord => Mnemonic::STNDW,
+            },
+        }
     }
 
     fn instruction(&self) -> String {
@@ -720,33 +619,65 @@ impl C6000Instruction for MemoryInstruction {
         )
     }
 
-    fn operands(&self) -> String {
-        let mode = self
-            .mode
-            .to_string()
-            .replace("R", self.base_register.to_string().as_str());
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::D)
+    }
+
+    fn side(&self) -> Option<bool> {
+        Some(self.side)
+    }
+
+    fn data_path_side(&self) -> Option<bool> {
+        Some(self.register.side())
+    }
+
+    fn reads(&self) -> HashSet<RegisterFile> {
+        let data = (self.instruction_type == MemoryInstructionType::Store).then_some(self.register);
+        register_set(
+            self.condition_register()
+                .into_iter()
+                .chain([self.base_register])
+                .chain(self.mode.get_register())
+                .chain(data)
+                .map(RegisterFile::from),
+        )
+    }
+
+    fn writes(&self) -> HashSet<RegisterFile> {
+        let data = (self.instruction_type == MemoryInstructionType::Load).then_some(self.register);
+        let base = self.mode.writes_back().then_some(self.base_register);
+        register_set(data.into_iter().chain(base).map(RegisterFile::from))
+    }
+
+    fn operand_list(&self) -> Vec<Operand> {
+        let memory = Operand::Memory {
+            base_register: self.base_register,
+            mode: self.mode,
+        };
+        let register = Operand::from(self.register);
+        if self.instruction_type == MemoryInstructionType::Load {
+            vec![memory, register]
+        } else {
+            vec![register, memory]
+        }
+    }
+
+    fn comment(&self) -> Option<String> {
         let shift_by = match self.data_size {
             DataSize::Byte | DataSize::ByteUnsigned => 0,
             DataSize::HalfWord | DataSize::HalfWordUnsigned => 1,
             DataSize::Word | DataSize::NonAlignedWord => 2,
             DataSize::DoubleWord | DataSize::NonAlignedDoubleWord => 3, // NonAlignedDoubleWord varies based on sc field
         };
-        let comment = {
-            if let Some(register) = self.mode.get_register() {
-                format!("({register} << {shift_by})")
-            } else if let Some(constant) = self.mode.get_constant()
-                && constant != 0
-            {
-                let result = constant << shift_by;
-                format!("({constant} << {shift_by} = 0x{result:04X})")
-            } else {
-                String::new()
-            }
-        };
-        if self.instruction_type == MemoryInstructionType::Load {
-            format!("{mode}, {} {comment}", self.register)
+        if let Some(register) = self.mode.get_register() {
+            Some(format!("({register} << {shift_by})"))
+        } else if let Some(constant) = self.mode.get_constant()
+            && constant != 0
+        {
+            let result = constant << shift_by;
+            Some(format!("({constant} << {shift_by} = 0x{result:04X})"))
         } else {
-            format!("{}, {mode} {comment}", self.register)
+            None
         }
     }
 
@@ -758,3 +689,35 @@ impl C6000Instruction for MemoryInstruction {
         &mut self.instruction_data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        instruction::{C6000Instruction, InstructionInput, fphead::CompactInstructionHeader},
+        read_compact_instruction,
+    };
+
+    fn decode_compact(opcode: u32, fphead: u32) -> String {
+        let fphead = CompactInstructionHeader::new(&InstructionInput {
+            opcode: fphead,
+            fphead: None,
+            pce1_address: 0,
+        })
+        .unwrap();
+        read_compact_instruction(InstructionInput {
+            opcode,
+            fphead: Some(fphead),
+            pce1_address: 0,
+        })
+        .unwrap()
+        .instruction()
+    }
+
+    #[test]
+    fn compact_stores_ignore_signedness_of_data_size() {
+        // The secondary data size of this header is an unsigned byte, which
+        // used to be listed as STBU
+        assert_eq!(decode_compact(0xC2A5, 0xE0000000), "STB.D2T1");
+        assert_eq!(decode_compact(0xC2AD, 0xE0000000), "LDBU.D2T1");
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Mnemonic {
+    // Branching
+    B,
+    BNOP,
+    CALLP,
+
+    // Loads
+    LDB,
+    LDBU,
+    LDH,
+    LDHU,
+    LDW,
+    LDNW,
+    LDDW,
+    LDNDW,
+
+    // Stores
+    STB,
+    STH,
+    STW,
+    STNW,
+    STDW,
+    STNDW,
+
+    // Moves
+    MV,
+    MVC,
+    MVD,
+    MVK,
+    MVKH,
+    ZERO,
+
+    // Arithmetic
+    ADDKPC,
+
+    NOP,
+    IDLE,
+
+    /// Compact instruction header (``.fphead``).
+    FPHEAD,
+    /// Opcode which couldn't be decoded.
+    INVALID,
+}
+
+/// Coarse classification of instructions, used for analyses which
+/// don't depend on the exact operation.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum InstructionClass {
+    Branch,
+    Load,
+    Store,
+    Move,
+    Arithmetic,
+    Nop,
+    Header,
+    Invalid,
+}
+
+impl Mnemonic {
+    pub fn class(&self) -> InstructionClass {
+        match self {
+            Self::B | Self::BNOP | Self::CALLP => InstructionClass::Branch,
+            Self::LDB
+            | Self::LDBU
+            | Self::LDH
+            | Self::LDHU
+            | Self::LDW
+            | Self::LDNW
+            | Self::LDDW
+            | Self::LDNDW => InstructionClass::Load,
+            Self::STB | Self::STH | Self::STW | Self::STNW | Self::STDW | Self::STNDW => {
+                InstructionClass::Store
+            }
+            Self::MV | Self::MVC | Self::MVD | Self::MVK | Self::MVKH | Self::ZERO => {
+                InstructionClass::Move
+            }
+            Self::ADDKPC => InstructionClass::Arithmetic,
+            Self::NOP | Self::IDLE => InstructionClass::Nop,
+            Self::FPHEAD => InstructionClass::Header,
+            Self::INVALID => InstructionClass::Invalid,
+        }
+    }
+}
+
+impl Display for Mnemonic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::B => write!(f, "B"),
+            Self::BNOP => write!(f, "BNOP"),
+            Self::CALLP => write!(f, "CALLP"),
+            Self::LDB => write!(f, "LDB"),
+            Self::LDBU => write!(f, "LDBU"),
+            Self::LDH => write!(f, "LDH"),
+            Self::LDHU => write!(f, "LDHU"),
+            Self::LDW => write!(f, "LDW"),
+            Self::LDNW => write!(f, "LDNW"),
+            Self::LDDW => write!(f, "LDDW"),
+            Self::LDNDW => write!(f, "LDNDW"),
+            Self::STB => write!(f, "STB"),
+            Self::STH => write!(f, "STH"),
+            Self::STW => write!(f, "STW"),
+            Self::STNW => write!(f, "STNW"),
+            Self::STDW => write!(f, "STDW"),
+            Self::STNDW => write!(f, "STNDW"),
+            Self::MV => write!(f, "MV"),
+            Self::MVC => write!(f, "MVC"),
+            Self::MVD => write!(f, "MVD"),
+            Self::MVK => write!(f, "MVK"),
+            Self::MVKH => write!(f, "MVKH"),
+            Self::ZERO => write!(f, "ZERO"),
+            Self::ADDKPC => write!(f, "ADDKPC"),
+            Self::NOP => write!(f, "NOP"),
+            Self::IDLE => write!(f, "IDLE"),
+            Self::FPHEAD => write!(f, ".fphead"),
+            Self::INVALID => write!(f, "INVALID"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mnemonics_are_classified() {
+        assert_eq!(Mnemonic::CALLP.class(), InstructionClass::Branch);
+        assert_eq!(Mnemonic::LDNDW.class(), InstructionClass::Load);
+        assert_eq!(Mnemonic::STB.class(), InstructionClass::Store);
+        assert_eq!(Mnemonic::ZERO.class(), InstructionClass::Move);
+        assert_eq!(Mnemonic::IDLE.class(), InstructionClass::Nop);
+        assert_eq!(Mnemonic::FPHEAD.to_string(), ".fphead");
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Result;
 use crate::instruction::{
-    C6000Instruction, ConditionalOperation, InstructionData, Unit,
-    parser::{ParsedVariable, ParsingInstruction, parse},
-    register::{ControlRegister, Register, RegisterFile},
+    C6000Instruction, ConditionalOperation, InstructionData, InstructionInput, Unit,
+    mnemonic::Mnemonic,
+    operand::Operand,
+    parser::{InstructionFormat, ParsedVariable, ParsingInstruction},
+    register::{ControlRegister, Register, RegisterFile, register_set},
 };
 
 pub struct MoveConstantInstruction {
@@ -13,10 +18,10 @@ pub struct MoveConstantInstruction {
 }
 
 impl C6000Instruction for MoveConstantInstruction {
-    fn new(input: &super::InstructionInput) -> std::io::Result<Self> {
-        let format_combinations = [
-            (
-                Unit::S,
+    fn formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "Scst16",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -44,8 +49,8 @@ impl C6000Instruction for MoveConstantInstruction {
                     },
                 ],
             ),
-            (
-                Unit::L,
+            InstructionFormat::new(
+                "Lcst5",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -77,8 +82,8 @@ impl C6000Instruction for MoveConstantInstruction {
                     },
                 ],
             ),
-            (
-                Unit::D,
+            InstructionFormat::new(
+                "Dcst5",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -104,46 +109,13 @@ impl C6000Instruction for MoveConstantInstruction {
                     },
                 ],
             ),
-        ];
-        for (unit, format) in format_combinations {
-            let Ok(parsed_variables) = parse(input.opcode, format.as_slice()) else {
-                continue;
-            };
-            let p_bit = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool()?;
-            let constant = ParsedVariable::try_get(&parsed_variables, "cst")?.get_u32()?;
-            let destination = ParsedVariable::try_get(&parsed_variables, "dst")?.get_register()?;
-            let high = {
-                if unit == Unit::S {
-                    ParsedVariable::try_get(&parsed_variables, "h")?.get_bool()?
-                } else {
-                    false
-                }
-            };
-            let conditional_operation =
-                ParsedVariable::try_get(&parsed_variables, "cond")?.get_conditional_operation()?;
-            return Ok(Self {
-                high,
-                constant,
-                destination,
-                unit,
-                instruction_data: InstructionData {
-                    opcode: input.opcode,
-                    conditional_operation,
-                    p_bit,
-                    ..Default::default()
-                },
-            });
-        }
-        Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("Not a Move Constant instruction: No matches found."),
-        ))
+        ]
     }
 
-    fn new_compact(input: &super::InstructionInput) -> std::io::Result<Self> {
-        let format_combinations = [
-            (
-                Unit::S,
+    fn compact_formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "Smvk8",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("s"),
@@ -174,8 +146,8 @@ impl C6000Instruction for MoveConstantInstruction {
                     },
                 ],
             ),
-            (
-                Unit::L,
+            InstructionFormat::new(
+                "Lx5",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("s"),
@@ -199,18 +171,129 @@ impl C6000Instruction for MoveConstantInstruction {
                     },
                 ],
             ),
-        ];
-        for (unit, format) in format_combinations {
-            let Ok(parsed_variables) = parse(input.opcode, format.as_slice()) else {
-                continue;
-            };
-            let mut constant = ParsedVariable::try_get(&parsed_variables, "cst20")?.get_u8()?;
-            constant += ParsedVariable::try_get(&parsed_variables, "cst43")?.get_u8()? << 3;
+            InstructionFormat::new(
+                "LSDx1c",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 2,
+                        value: 0b11,
+                    },
+                    ParsingInstruction::LSDUnit {
+                        name: String::from("unit"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 2,
+                        value: 0b11,
+                    },
+                    ParsingInstruction::Register {
+                        size: 3,
+                        name: String::from("dst"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 3,
+                        value: 0b010,
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 1,
+                        name: String::from("cst"),
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 2,
+                        name: String::from("cc"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "LSDx1",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 2,
+                        value: 0b11,
+                    },
+                    ParsingInstruction::LSDUnit {
+                        name: String::from("unit"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 2,
+                        value: 0b11,
+                    },
+                    ParsingInstruction::Register {
+                        size: 3,
+                        name: String::from("dst"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 3,
+                        value: 0b110,
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 1,
+                        name: String::from("cst"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 2,
+                        value: 0b00,
+                    },
+                ],
+            ),
+        ]
+    }
+
+    fn from_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let unit = match format {
+            "Scst16" => Unit::S,
+            "Lcst5" => Unit::L,
+            _ => Unit::D,
+        };
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+        let constant = ParsedVariable::try_get(parsed_variables, "cst")?.get_u32()?;
+        let destination = ParsedVariable::try_get(parsed_variables, "dst")?.get_register()?;
+        let high = {
+            if unit == Unit::S {
+                ParsedVariable::try_get(parsed_variables, "h")?.get_bool()?
+            } else {
+                false
+            }
+        };
+        let conditional_operation =
+            ParsedVariable::try_get(parsed_variables, "cond")?.get_conditional_operation()?;
+        Ok(Self {
+            high,
+            constant,
+            destination,
+            unit,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                conditional_operation,
+                p_bit,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn from_compact_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        if format == "Smvk8" || format == "Lx5" {
+            let unit = if format == "Smvk8" { Unit::S } else { Unit::L };
+            let mut constant = ParsedVariable::try_get(parsed_variables, "cst20")?.get_u8()?;
+            constant += ParsedVariable::try_get(parsed_variables, "cst43")?.get_u8()? << 3;
             if unit == Unit::S {
-                constant += ParsedVariable::try_get(&parsed_variables, "cst65")?.get_u8()? << 5;
-                constant += ParsedVariable::try_get(&parsed_variables, "cst7")?.get_u8()? << 7;
+                constant += ParsedVariable::try_get(parsed_variables, "cst65")?.get_u8()? << 5;
+                constant += ParsedVariable::try_get(parsed_variables, "cst7")?.get_u8()? << 7;
             }
-            let destination = ParsedVariable::try_get(&parsed_variables, "dst")?.get_register()?;
+            let destination = ParsedVariable::try_get(parsed_variables, "dst")?.get_register()?;
             return Ok(Self {
                 high: false,
                 constant: constant as u32,
@@ -224,122 +307,43 @@ impl C6000Instruction for MoveConstantInstruction {
             });
         }
 
-        let multiunit_formats = [
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match {
-                    size: 2,
-                    value: 0b11,
-                },
-                ParsingInstruction::LSDUnit {
-                    name: String::from("unit"),
-                },
-                ParsingInstruction::Match {
-                    size: 2,
-                    value: 0b11,
-                },
-                ParsingInstruction::Register {
-                    size: 3,
-                    name: String::from("dst"),
-                },
-                ParsingInstruction::Match {
-                    size: 3,
-                    value: 0b010,
-                },
-                ParsingInstruction::Unsigned {
-                    size: 1,
-                    name: String::from("cst"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 2,
-                    name: String::from("cc"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("s"),
-                },
-                ParsingInstruction::Match {
-                    size: 2,
-                    value: 0b11,
-                },
-                ParsingInstruction::LSDUnit {
-                    name: String::from("unit"),
-                },
-                ParsingInstruction::Match {
-                    size: 2,
-                    value: 0b11,
-                },
-                ParsingInstruction::Register {
-                    size: 3,
-                    name: String::from("dst"),
-                },
-                ParsingInstruction::Match {
-                    size: 3,
-                    value: 0b110,
-                },
-                ParsingInstruction::Unsigned {
-                    size: 1,
-                    name: String::from("cst"),
-                },
-                ParsingInstruction::Match {
-                    size: 2,
-                    value: 0b00,
-                },
-            ],
-        ];
-
-        for format in multiunit_formats {
-            let Ok(parsed_variables) = parse(input.opcode as u32, format.as_slice()) else {
-                continue;
-            };
-            let constant = ParsedVariable::try_get(&parsed_variables, "cst")?.get_u32()?;
-            let destination = ParsedVariable::try_get(&parsed_variables, "dst")?.get_register()?;
-            let unit = ParsedVariable::try_get(&parsed_variables, "unit")?.get_unit()?;
-            let conditional_operation = {
-                if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "cc") {
-                    match variable.get_u8()? {
-                        0 => Some(ConditionalOperation::NonZero(Register::A(0))),
-                        1 => Some(ConditionalOperation::Zero(Register::A(0))),
-                        2 => Some(ConditionalOperation::NonZero(Register::B(0))),
-                        3 => Some(ConditionalOperation::Zero(Register::B(0))),
-                        _ => None,
-                    }
-                } else {
-                    None
+        let constant = ParsedVariable::try_get(parsed_variables, "cst")?.get_u32()?;
+        let destination = ParsedVariable::try_get(parsed_variables, "dst")?.get_register()?;
+        let unit = ParsedVariable::try_get(parsed_variables, "unit")?.get_unit()?;
+        let conditional_operation = {
+            if let Ok(variable) = ParsedVariable::try_get(parsed_variables, "cc") {
+                match variable.get_u8()? {
+                    0 => Some(ConditionalOperation::NonZero(Register::A(0))),
+                    1 => Some(ConditionalOperation::Zero(Register::A(0))),
+                    2 => Some(ConditionalOperation::NonZero(Register::B(0))),
+                    3 => Some(ConditionalOperation::Zero(Register::B(0))),
+                    _ => None,
                 }
-            };
-            return Ok(Self {
-                high: false,
-                constant,
-                destination,
-                unit,
-                instruction_data: InstructionData {
-                    opcode: input.opcode,
-                    conditional_operation,
-                    compact: true,
-                    ..Default::default()
-                },
-            });
-        }
-
-        Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("Not a Move Constant instruction: No matches found."),
-        ))
+            } else {
+                None
+            }
+        };
+        Ok(Self {
+            high: false,
+            constant,
+            destination,
+            unit,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                conditional_operation,
+                compact: true,
+                ..Default::default()
+            },
+        })
     }
 
-    fn instruction_clean(&self) -> String {
+    fn mnemonic(&self) -> Mnemonic {
         if self.high {
-            String::from("MVKH")
+            Mnemonic::MVKH
+        } else if self.constant == 0 {
+            Mnemonic::ZERO
         } else {
-            if self.constant == 0 {
-                String::from("ZERO")
-            } else {
-                String::from("MVK")
-            }
+            Mnemonic::MVK
         }
     }
 
@@ -351,11 +355,37 @@ impl C6000Instruction for MoveConstantInstruction {
         value
     }
 
-    fn operands(&self) -> String {
+    fn unit(&self) -> Option<Unit> {
+        Some(self.unit)
+    }
+
+    fn side(&self) -> Option<bool> {
+        Some(self.destination.side())
+    }
+
+    fn reads(&self) -> HashSet<RegisterFile> {
+        // MVKH keeps the low half of the destination
+        let destination = self.high.then_some(self.destination);
+        register_set(
+            self.condition_register()
+                .into_iter()
+                .chain(destination)
+                .map(RegisterFile::from),
+        )
+    }
+
+    fn writes(&self) -> HashSet<RegisterFile> {
+        register_set([RegisterFile::from(self.destination)])
+    }
+
+    fn operand_list(&self) -> Vec<Operand> {
         if !self.high && self.constant == 0 {
-            self.destination.to_string()
+            vec![Operand::from(self.destination)]
         } else {
-            format!("0x{:04X}, {}", self.constant, self.destination.to_string())
+            vec![
+                Operand::Immediate(self.constant),
+                Operand::from(self.destination),
+            ]
         }
     }
 
@@ -378,10 +408,88 @@ pub struct MoveRegisterInstruction {
 }
 
 impl MoveRegisterInstruction {
-    fn new_mv(opcode: u32) -> std::io::Result<Self> {
-        let format_combinations = [
-            (
-                Unit::S,
+    fn from_mv_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let unit = match format {
+            "Smv" => Unit::S,
+            "Lmvdw" | "Lmv" => Unit::L,
+            "Dmv" | "Dmvx" => Unit::D,
+            _ => Unit::M,
+        };
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+        let side = ParsedVariable::try_get(parsed_variables, "s")?.get_bool()?;
+        let source_register = ParsedVariable::try_get(parsed_variables, "src")?.get_register()?;
+        let destination_register =
+            ParsedVariable::try_get(parsed_variables, "dst")?.get_register()?;
+        let source = RegisterFile::GeneralPurpose(source_register);
+        let destination = RegisterFile::GeneralPurpose(destination_register);
+        let conditional_operation =
+            ParsedVariable::try_get(parsed_variables, "cond")?.get_conditional_operation()?;
+        let delayed = unit == Unit::M;
+        Ok(Self {
+            source,
+            destination,
+            unit,
+            side,
+            delayed,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                conditional_operation,
+                p_bit,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn from_mvc_format(
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+        let control_register =
+            ParsedVariable::try_get(parsed_variables, "crlo")?.get_control_register()?;
+        let (source, destination) = {
+            if let Ok(variable) = ParsedVariable::try_get(parsed_variables, "dst") {
+                let destination_register = variable.get_register()?;
+                (
+                    RegisterFile::Control(control_register),
+                    RegisterFile::GeneralPurpose(destination_register),
+                )
+            } else {
+                let source_register =
+                    ParsedVariable::try_get(parsed_variables, "src")?.get_register()?;
+                (
+                    RegisterFile::GeneralPurpose(source_register),
+                    RegisterFile::Control(control_register),
+                )
+            }
+        };
+        let conditional_operation =
+            ParsedVariable::try_get(parsed_variables, "cond")?.get_conditional_operation()?;
+        Ok(Self {
+            source,
+            destination,
+            unit: Unit::S,
+            side: true,
+            delayed: false,
+            instruction_data: InstructionData {
+                opcode: input.opcode,
+                conditional_operation,
+                p_bit,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+impl C6000Instruction for MoveRegisterInstruction {
+    fn formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "Smv",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -410,8 +518,8 @@ impl MoveRegisterInstruction {
                     },
                 ],
             ),
-            (
-                Unit::L,
+            InstructionFormat::new(
+                "Lmvdw",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -436,8 +544,8 @@ impl MoveRegisterInstruction {
                     },
                 ],
             ),
-            (
-                Unit::L,
+            InstructionFormat::new(
+                "Lmv",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -457,7 +565,7 @@ impl MoveRegisterInstruction {
                         name: String::from("x"),
                     },
                     ParsingInstruction::Match { size: 5, value: 0 },
-                    ParsingInstruction::Register {
+                    ParsingInstruction::RegisterCrosspath {
                         size: 5,
                         name: String::from("src"),
                     },
@@ -470,8 +578,8 @@ impl MoveRegisterInstruction {
                     },
                 ],
             ),
-            (
-                Unit::D,
+            InstructionFormat::new(
+                "Dmv",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -496,8 +604,8 @@ impl MoveRegisterInstruction {
                     },
                 ],
             ),
-            (
-                Unit::D,
+            InstructionFormat::new(
+                "Dmvx",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -526,8 +634,8 @@ impl MoveRegisterInstruction {
                     },
                 ],
             ),
-            (
-                Unit::M,
+            InstructionFormat::new(
+                "Mmvd",
                 vec![
                     ParsingInstruction::Bit {
                         name: String::from("p"),
@@ -559,279 +667,163 @@ impl MoveRegisterInstruction {
                     },
                 ],
             ),
-        ];
-        for (unit, format) in format_combinations {
-            let Ok(parsed_variables) = parse(opcode, format.as_slice()) else {
-                continue;
-            };
-            let p_bit = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool()?;
-            let side = ParsedVariable::try_get(&parsed_variables, "s")?.get_bool()?;
-            let source_register =
-                ParsedVariable::try_get(&parsed_variables, "src")?.get_register()?;
-            let destination_register =
-                ParsedVariable::try_get(&parsed_variables, "dst")?.get_register()?;
-            let source = RegisterFile::GeneralPurpose(source_register);
-            let destination = RegisterFile::GeneralPurpose(destination_register);
-            let conditional_operation =
-                ParsedVariable::try_get(&parsed_variables, "cond")?.get_conditional_operation()?;
-            let delayed = if unit == Unit::M { true } else { false };
-            return Ok(Self {
-                source,
-                destination,
-                unit,
-                side,
-                delayed,
-                instruction_data: InstructionData {
-                    opcode,
-                    conditional_operation,
-                    p_bit,
-                    ..Default::default()
-                },
-            });
-        }
-        Err(std::io::Error::other("Not MV/MVD"))
+            InstructionFormat::new(
+                "Smvcfrom",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::BitMatch {
+                        name: String::from("s"),
+                        value: true,
+                    },
+                    ParsingInstruction::Match {
+                        size: 10,
+                        value: 0b0011111000,
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("x"),
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 5,
+                        name: String::from("crhi"),
+                    },
+                    ParsingInstruction::ControlRegister {
+                        size: 5,
+                        name: String::from("crlo"),
+                    },
+                    ParsingInstruction::RegisterCrosspath {
+                        size: 5,
+                        name: String::from("dst"),
+                    },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "Smvcto",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("p"),
+                    },
+                    ParsingInstruction::BitMatch {
+                        name: String::from("s"),
+                        value: true,
+                    },
+                    ParsingInstruction::Match {
+                        size: 10,
+                        value: 0b0011101000,
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("x"),
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 5,
+                        name: String::from("crhi"),
+                    },
+                    ParsingInstruction::RegisterCrosspath {
+                        size: 5,
+                        name: String::from("src"),
+                    },
+                    ParsingInstruction::ControlRegister {
+                        size: 5,
+                        name: String::from("crlo"),
+                    },
+                    ParsingInstruction::ConditionalOperation {
+                        name: String::from("cond"),
+                    },
+                ],
+            ),
+        ]
     }
 
-    fn new_mvc(opcode: u32) -> std::io::Result<Self> {
-        let format_combinations = [
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::BitMatch {
-                    name: String::from("s"),
-                    value: true,
-                },
-                ParsingInstruction::Match {
-                    size: 10,
-                    value: 0b0011111000,
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("x"),
-                },
-                ParsingInstruction::Match { size: 5, value: 0 },
-                ParsingInstruction::ControlRegister {
-                    size: 5,
-                    name: String::from("crlo"),
-                },
-                ParsingInstruction::RegisterCrosspath {
-                    size: 5,
-                    name: String::from("dst"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::BitMatch {
-                    name: String::from("s"),
-                    value: true,
-                },
-                ParsingInstruction::Match {
-                    size: 10,
-                    value: 0b0011101000,
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("x"),
-                },
-                ParsingInstruction::Match { size: 5, value: 0 },
-                ParsingInstruction::RegisterCrosspath {
-                    size: 5,
-                    name: String::from("src"),
-                },
-                ParsingInstruction::ControlRegister {
-                    size: 5,
-                    name: String::from("crlo"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::BitMatch {
-                    name: String::from("s"),
-                    value: true,
-                },
-                ParsingInstruction::Match {
-                    size: 10,
-                    value: 0b0011111000,
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("x"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 5,
-                    name: String::from("crhi"),
-                },
-                ParsingInstruction::ControlRegister {
-                    size: 5,
-                    name: String::from("crlo"),
-                },
-                ParsingInstruction::RegisterCrosspath {
-                    size: 5,
-                    name: String::from("dst"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-            vec![
-                ParsingInstruction::Bit {
-                    name: String::from("p"),
-                },
-                ParsingInstruction::BitMatch {
-                    name: String::from("s"),
-                    value: true,
-                },
-                ParsingInstruction::Match {
-                    size: 10,
-                    value: 0b0011101000,
-                },
-                ParsingInstruction::Bit {
-                    name: String::from("x"),
-                },
-                ParsingInstruction::Unsigned {
-                    size: 5,
-                    name: String::from("crhi"),
-                },
-                ParsingInstruction::RegisterCrosspath {
-                    size: 5,
-                    name: String::from("src"),
-                },
-                ParsingInstruction::ControlRegister {
-                    size: 5,
-                    name: String::from("crlo"),
-                },
-                ParsingInstruction::ConditionalOperation {
-                    name: String::from("cond"),
-                },
-            ],
-        ];
-        for format in format_combinations {
-            let Ok(parsed_variables) = parse(opcode, format.as_slice()) else {
-                continue;
-            };
-            let p_bit = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool()?;
-            let control_register =
-                ParsedVariable::try_get(&parsed_variables, "crlo")?.get_control_register()?;
-            let (source, destination) = {
-                if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "dst") {
-                    let destination_register = variable.get_register()?;
-                    (
-                        RegisterFile::Control(control_register),
-                        RegisterFile::GeneralPurpose(destination_register),
-                    )
-                } else if let Ok(variable) = ParsedVariable::try_get(&parsed_variables, "src") {
-                    let source_register = variable.get_register()?;
-                    (
-                        RegisterFile::GeneralPurpose(source_register),
-                        RegisterFile::Control(control_register),
-                    )
-                } else {
-                    continue;
-                }
-            };
-            let conditional_operation =
-                ParsedVariable::try_get(&parsed_variables, "cond")?.get_conditional_operation()?;
-            return Ok(Self {
-                source,
-                destination,
-                unit: Unit::S,
-                side: true,
-                delayed: false,
-                instruction_data: InstructionData {
-                    opcode,
-                    conditional_operation,
-                    p_bit,
-                    ..Default::default()
-                },
-            });
-        }
-        Err(std::io::Error::other("Not MVC"))
+    fn compact_formats() -> Vec<InstructionFormat> {
+        vec![
+            InstructionFormat::new(
+                "LSDmv",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 2,
+                        value: 0b11,
+                    },
+                    ParsingInstruction::LSDUnit {
+                        name: String::from("unit"),
+                    },
+                    ParsingInstruction::Match { size: 1, value: 0 },
+                    ParsingInstruction::Bit {
+                        name: String::from("ms_bit"),
+                    },
+                    ParsingInstruction::Register {
+                        size: 3,
+                        name: String::from("src"),
+                    },
+                    ParsingInstruction::Unsigned {
+                        size: 2,
+                        name: String::from("ms"),
+                    },
+                    ParsingInstruction::Bit {
+                        name: String::from("x"),
+                    },
+                    ParsingInstruction::Register {
+                        size: 3,
+                        name: String::from("dst"),
+                    },
+                ],
+            ),
+            InstructionFormat::new(
+                "Sx1mvc",
+                vec![
+                    ParsingInstruction::Bit {
+                        name: String::from("s"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 6,
+                        value: 0b110111,
+                    },
+                    ParsingInstruction::Register {
+                        size: 3,
+                        name: String::from("src"),
+                    },
+                    ParsingInstruction::Match {
+                        size: 6,
+                        value: 0b110110,
+                    },
+                ],
+            ),
+        ]
     }
-}
 
-impl C6000Instruction for MoveRegisterInstruction {
-    fn new(input: &super::InstructionInput) -> std::io::Result<Self> {
-        if let Ok(ret_val) = Self::new_mv(input.opcode) {
-            return Ok(ret_val);
-        } else if let Ok(ret_val) = Self::new_mvc(input.opcode) {
-            return Ok(ret_val);
+    fn from_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        if format.starts_with("Smvc") {
+            Self::from_mvc_format(input, parsed_variables)
+        } else {
+            Self::from_mv_format(format, input, parsed_variables)
         }
-
-        Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("Not a Move Register instruction: No matches found."),
-        ))
     }
 
-    fn new_compact(input: &super::InstructionInput) -> std::io::Result<Self> {
-        let mv_format = [
-            ParsingInstruction::Bit {
-                name: String::from("s"),
-            },
-            ParsingInstruction::Match {
-                size: 2,
-                value: 0b11,
-            },
-            ParsingInstruction::LSDUnit {
-                name: String::from("unit"),
-            },
-            ParsingInstruction::Match { size: 1, value: 0 },
-            ParsingInstruction::Bit {
-                name: String::from("ms_bit"),
-            },
-            ParsingInstruction::Register {
-                size: 3,
-                name: String::from("src"),
-            },
-            ParsingInstruction::Unsigned {
-                size: 2,
-                name: String::from("ms"),
-            },
-            ParsingInstruction::Bit {
-                name: String::from("x"),
-            },
-            ParsingInstruction::Register {
-                size: 3,
-                name: String::from("dst"),
-            },
-        ];
-
-        let mvc_format = [
-            ParsingInstruction::Bit {
-                name: String::from("s"),
-            },
-            ParsingInstruction::Match {
-                size: 6,
-                value: 0b110111,
-            },
-            ParsingInstruction::Register {
-                size: 3,
-                name: String::from("src"),
-            },
-            ParsingInstruction::Match {
-                size: 6,
-                value: 0b110110,
-            },
-        ];
-
-        if let Ok(parsed_variables) = parse(input.opcode, &mv_format) {
-            let unit = ParsedVariable::try_get(&parsed_variables, "unit")?.get_unit()?;
-            let side = ParsedVariable::try_get(&parsed_variables, "s")?.get_bool()?;
-            let crosspath = ParsedVariable::try_get(&parsed_variables, "x")?.get_bool()?;
-            let ms_bit = ParsedVariable::try_get(&parsed_variables, "ms_bit")?.get_bool()?;
-            let ms = ParsedVariable::try_get(&parsed_variables, "ms")?.get_u8()?;
+    fn from_compact_format(
+        format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        if format == "LSDmv" {
+            let unit = ParsedVariable::try_get(parsed_variables, "unit")?.get_unit()?;
+            let side = ParsedVariable::try_get(parsed_variables, "s")?.get_bool()?;
+            let crosspath = ParsedVariable::try_get(parsed_variables, "x")?.get_bool()?;
+            let ms_bit = ParsedVariable::try_get(parsed_variables, "ms_bit")?.get_bool()?;
+            let ms = ParsedVariable::try_get(parsed_variables, "ms")?.get_u8()?;
             let mut source_register =
-                ParsedVariable::try_get(&parsed_variables, "src")?.get_register()?;
+                ParsedVariable::try_get(parsed_variables, "src")?.get_register()?;
             let mut destination_register =
-                ParsedVariable::try_get(&parsed_variables, "dst")?.get_register()?;
+                ParsedVariable::try_get(parsed_variables, "dst")?.get_register()?;
             if ms_bit {
                 destination_register += (ms) << 3;
             } else {
@@ -842,7 +834,7 @@ impl C6000Instruction for MoveRegisterInstruction {
             }
             let source = RegisterFile::GeneralPurpose(source_register);
             let destination = RegisterFile::GeneralPurpose(destination_register);
-            return Ok(Self {
+            Ok(Self {
                 source,
                 destination,
                 side,
@@ -853,14 +845,14 @@ impl C6000Instruction for MoveRegisterInstruction {
                     compact: true,
                     ..Default::default()
                 },
-            });
-        } else if let Ok(parsed_variables) = parse(input.opcode, &mvc_format) {
-            let side = ParsedVariable::try_get(&parsed_variables, "s")?.get_bool()?;
+            })
+        } else {
+            let side = ParsedVariable::try_get(parsed_variables, "s")?.get_bool()?;
             let source_register =
-                ParsedVariable::try_get(&parsed_variables, "src")?.get_register()?;
+                ParsedVariable::try_get(parsed_variables, "src")?.get_register()?;
             let source = RegisterFile::GeneralPurpose(source_register);
             let destination = RegisterFile::Control(ControlRegister::ILC);
-            return Ok(Self {
+            Ok(Self {
                 source,
                 destination,
                 side,
@@ -871,22 +863,17 @@ impl C6000Instruction for MoveRegisterInstruction {
                     compact: true,
                     ..Default::default()
                 },
-            });
+            })
         }
-
-        Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("Not a Move Register instruction: No matches found."),
-        ))
     }
 
-    fn instruction_clean(&self) -> String {
-        if self.destination.side() == None || self.source.side() == None {
-            String::from("MVC")
+    fn mnemonic(&self) -> Mnemonic {
+        if self.destination.side().is_none() || self.source.side().is_none() {
+            Mnemonic::MVC
         } else if self.delayed {
-            String::from("MVD")
+            Mnemonic::MVD
         } else {
-            String::from("MV")
+            Mnemonic::MV
         }
     }
 
@@ -897,20 +884,41 @@ impl C6000Instruction for MoveRegisterInstruction {
             self.unit.to_sided_string(self.side)
         );
 
-        if self.destination.side() == Some(!self.side) || self.source.side() == Some(!self.side) {
+        if self.uses_crosspath() {
             value += "X";
         }
         value
     }
 
-    fn operands(&self) -> String {
-        format!(
-            "{}, {}",
-            self.source.to_string(),
-            self.destination.to_string()
+    fn unit(&self) -> Option<Unit> {
+        Some(self.unit)
+    }
+
+    fn side(&self) -> Option<bool> {
+        Some(self.side)
+    }
+
+    fn uses_crosspath(&self) -> bool {
+        self.destination.side() == Some(!self.side) || self.source.side() == Some(!self.side)
+    }
+
+    fn reads(&self) -> HashSet<RegisterFile> {
+        register_set(
+            self.condition_register()
+                .map(RegisterFile::from)
+                .into_iter()
+                .chain([self.source]),
         )
     }
 
+    fn writes(&self) -> HashSet<RegisterFile> {
+        register_set([self.destination])
+    }
+
+    fn operand_list(&self) -> Vec<Operand> {
+        vec![Operand::from(self.source), Operand::from(self.destination)]
+    }
+
     fn instruction_data(&self) -> &InstructionData {
         &self.instruction_data
     }
@@ -919,3 +927,31 @@ impl C6000Instruction for MoveRegisterInstruction {
         &mut self.instruction_data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{instruction::InstructionInput, read_instruction};
+
+    fn decode(opcode: u32) -> String {
+        let instruction = read_instruction(InstructionInput {
+            opcode,
+            fphead: None,
+            pce1_address: 0,
+        })
+        .unwrap();
+        format!("{} {}", instruction.instruction(), instruction.operands())
+    }
+
+    #[test]
+    fn lmv_decodes_both_opcodes() {
+        assert_eq!(decode(0x020C0058), "MV.L1 A3, A4");
+        assert_eq!(decode(0x020C0FD8), "MV.L1 A3, A4");
+        assert_eq!(decode(0x020C005A), "MV.L2 B3, B4");
+    }
+
+    #[test]
+    fn lmv_reads_source_through_cross_path() {
+        assert_eq!(decode(0x020C1058), "MV.L1X B3, A4");
+        assert_eq!(decode(0x020C105A), "MV.L2X A3, B4");
+    }
+}
@@ -1,8 +1,12 @@
+use crate::error::Result;
 use crate::instruction::{
-    C6000Instruction, InstructionData,
-    parser::{ParsedVariable, ParsingInstruction, parse},
+    C6000Instruction, InstructionData, InstructionInput,
+    mnemonic::Mnemonic,
+    operand::Operand,
+    parser::{InstructionFormat, ParsedVariable, ParsingInstruction},
+    timing::Timing,
 };
-use std::io::{Error, ErrorKind, Result};
+use std::collections::HashMap;
 
 pub struct NOPInstruction {
     pub count: u8,
@@ -10,22 +14,47 @@ pub struct NOPInstruction {
 }
 
 impl C6000Instruction for NOPInstruction {
-    fn new(input: &super::InstructionInput) -> Result<Self> {
-        let format = [
-            ParsingInstruction::Bit {
-                name: String::from("p"),
-            },
-            ParsingInstruction::Match { size: 12, value: 0 },
-            ParsingInstruction::Unsigned {
-                size: 4,
-                name: String::from("src"),
-            },
-            ParsingInstruction::Match { size: 15, value: 0 },
-        ];
-        let parsed_variables = parse(input.opcode, &format)
-            .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("Not a NOP/IDLE: {e}")))?;
-        let p_bit = ParsedVariable::try_get(&parsed_variables, "p")?.get_bool()?;
-        let count = ParsedVariable::try_get(&parsed_variables, "src")?.get_u8()?;
+    fn formats() -> Vec<InstructionFormat> {
+        vec![InstructionFormat::new(
+            "nop",
+            vec![
+                ParsingInstruction::Bit {
+                    name: String::from("p"),
+                },
+                ParsingInstruction::Match { size: 12, value: 0 },
+                ParsingInstruction::Unsigned {
+                    size: 4,
+                    name: String::from("src"),
+                },
+                ParsingInstruction::Match { size: 1, value: 0 },
+                ParsingInstruction::Reserved { size: 14 },
+            ],
+        )]
+    }
+
+    fn compact_formats() -> Vec<InstructionFormat> {
+        vec![InstructionFormat::new(
+            "Unop",
+            vec![
+                ParsingInstruction::Match {
+                    size: 13,
+                    value: 0xC6E,
+                },
+                ParsingInstruction::Unsigned {
+                    size: 3,
+                    name: String::from("N3"),
+                },
+            ],
+        )]
+    }
+
+    fn from_format(
+        _format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let p_bit = ParsedVariable::try_get(parsed_variables, "p")?.get_bool()?;
+        let count = ParsedVariable::try_get(parsed_variables, "src")?.get_u8()?;
         Ok(NOPInstruction {
             count,
             instruction_data: InstructionData {
@@ -36,20 +65,12 @@ impl C6000Instruction for NOPInstruction {
         })
     }
 
-    fn new_compact(input: &super::InstructionInput) -> Result<Self> {
-        let format = [
-            ParsingInstruction::Match {
-                size: 13,
-                value: 0xC6E,
-            },
-            ParsingInstruction::Unsigned {
-                size: 3,
-                name: String::from("N3"),
-            },
-        ];
-        let parsed_variables = parse(input.opcode, &format)
-            .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("Not a NOP/IDLE: {e}")))?;
-        let count = ParsedVariable::try_get(&parsed_variables, "N3")?.get_u8()?;
+    fn from_compact_format(
+        _format: &str,
+        input: &InstructionInput,
+        parsed_variables: &HashMap<String, ParsedVariable>,
+    ) -> Result<Self> {
+        let count = ParsedVariable::try_get(parsed_variables, "N3")?.get_u8()?;
         Ok(NOPInstruction {
             count,
             instruction_data: InstructionData {
@@ -60,19 +81,31 @@ impl C6000Instruction for NOPInstruction {
         })
     }
 
+    fn mnemonic(&self) -> Mnemonic {
+        if self.count == 0b1111 {
+            Mnemonic::IDLE
+        } else {
+            Mnemonic::NOP
+        }
+    }
+
     fn instruction(&self) -> String {
+        self.instruction_clean()
+    }
+
+    fn timing(&self) -> Timing {
         if self.count == 0b1111 {
-            String::from("IDLE")
+            Timing::default()
         } else {
-            String::from("NOP")
+            Timing::default().with_nop_cycles(self.count + 1)
         }
     }
 
-    fn operands(&self) -> String {
+    fn operand_list(&self) -> Vec<Operand> {
         if self.count > 0 && self.count != 0b1111 {
-            format!("{}", self.count + 1)
+            vec![Operand::Cycles(self.count + 1)]
         } else {
-            String::new()
+            Vec::new()
         }
     }
 
@@ -0,0 +1,180 @@
+use std::fmt::Display;
+
+use crate::instruction::{
+    memory::AddressGeneratorMode,
+    register::{ControlRegister, Register, RegisterFile},
+};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Operand {
+    /// General-purpose register.
+    Register(Register),
+    /// Pair of general-purpose registers (``A1:A0``, ``B5:B4``...).
+    RegisterPair(Register),
+    ControlRegister(ControlRegister),
+    /// Constant value, written in hexadecimal.
+    Immediate(u32),
+    /// Number of cycles, such as the count of a ``NOP`` or ``BNOP``.
+    Cycles(u8),
+    /// Memory address accessed by a load or a store.
+    Memory {
+        base_register: Register,
+        mode: AddressGeneratorMode,
+    },
+    /// Address of a branch, relative to the PCE1 address.
+    BranchTarget {
+        pce1_address: u32,
+        displacement: i32,
+    },
+    /// Setting written as a keyword, used by the ``.fphead`` directive.
+    Keyword(&'static str),
+    /// Bit field written in binary, most significant bit first.
+    Bits {
+        value: u32,
+        size: u8,
+    },
+}
+
+impl Operand {
+    /// Returns the absolute address of a branch target, if known.
+    pub fn target_address(&self) -> Option<u32> {
+        if let Self::BranchTarget {
+            pce1_address,
+            displacement,
+        } = self
+            && *pce1_address != 0
+        {
+            Some(pce1_address.wrapping_add_signed(*displacement))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the register read or written through the operand, if any.
+    pub fn register(&self) -> Option<Register> {
+        match self {
+            Self::Register(register) | Self::RegisterPair(register) => Some(*register),
+            _ => None,
+        }
+    }
+}
+
+impl From<Register> for Operand {
+    fn from(register: Register) -> Self {
+        match register {
+            Register::A(_) | Register::B(_) => Self::Register(register),
+            Register::APair(_, _) | Register::BPair(_, _) => Self::RegisterPair(register),
+        }
+    }
+}
+
+impl From<RegisterFile> for Operand {
+    fn from(register: RegisterFile) -> Self {
+        match register {
+            RegisterFile::GeneralPurpose(register) => Self::from(register),
+            RegisterFile::Control(register) => Self::ControlRegister(register),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Register(register) | Self::RegisterPair(register) => write!(f, "{register}"),
+            Self::ControlRegister(register) => write!(f, "{register}"),
+            Self::Immediate(value) => write!(f, "0x{value:04X}"),
+            Self::Cycles(count) => write!(f, "{count}"),
+            Self::Memory {
+                base_register,
+                mode,
+            } => {
+                let offset = match mode.get_register() {
+                    Some(register) => register.to_string(),
+                    None => mode.get_constant().unwrap_or_default().to_string(),
+                };
+                match mode {
+                    AddressGeneratorMode::NegativeR(_) | AddressGeneratorMode::Negative(_) => {
+                        write!(f, "*-{base_register}[{offset}]")
+                    }
+                    AddressGeneratorMode::PositiveR(_) | AddressGeneratorMode::Positive(_) => {
+                        write!(f, "*+{base_register}[{offset}]")
+                    }
+                    AddressGeneratorMode::PredecrementR(_)
+                    | AddressGeneratorMode::Predecrement(_) => {
+                        write!(f, "*--{base_register}[{offset}]")
+                    }
+                    AddressGeneratorMode::PreincrementR(_)
+                    | AddressGeneratorMode::Preincrement(_) => {
+                        write!(f, "*++{base_register}[{offset}]")
+                    }
+                    AddressGeneratorMode::PostdecrementR(_)
+                    | AddressGeneratorMode::Postdecrement(_) => {
+                        write!(f, "*{base_register}--[{offset}]")
+                    }
+                    AddressGeneratorMode::PostincrementR(_)
+                    | AddressGeneratorMode::Postincrement(_) => {
+                        write!(f, "*{base_register}++[{offset}]")
+                    }
+                }
+            }
+            Self::BranchTarget { displacement, .. } => {
+                let Some(address) = self.target_address() else {
+                    return write!(f, "ERROR PCE1 = 0");
+                };
+                write!(
+                    f,
+                    "0x{address:08X} (PCE1{}0x{:08X})",
+                    if displacement.is_positive() { "+" } else { "-" },
+                    displacement.unsigned_abs()
+                )
+            }
+            Self::Keyword(keyword) => write!(f, "{keyword}"),
+            Self::Bits { value, size } => write!(f, "{value:0width$b}", width = *size as usize),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branch_target_resolves_relative_to_pce1() {
+        let operand = Operand::BranchTarget {
+            pce1_address: 0x8000_0020,
+            displacement: -0x40,
+        };
+        assert_eq!(operand.target_address(), Some(0x7FFF_FFE0));
+        assert_eq!(operand.to_string(), "0x7FFFFFE0 (PCE1-0x00000040)");
+    }
+
+    #[test]
+    fn registers_are_classified_by_width() {
+        assert_eq!(
+            Operand::from(Register::B(3)),
+            Operand::Register(Register::B(3))
+        );
+        assert_eq!(
+            Operand::from(Register::APair(5, 4)),
+            Operand::RegisterPair(Register::APair(5, 4))
+        );
+    }
+
+    #[test]
+    fn memory_operand_writes_base_register() {
+        let operand = Operand::Memory {
+            base_register: Register::B(15),
+            mode: AddressGeneratorMode::Preincrement(2),
+        };
+        assert_eq!(operand.to_string(), "*++B15[2]");
+    }
+
+    #[test]
+    fn memory_operand_keeps_register_offset() {
+        let operand = Operand::Memory {
+            base_register: Register::A(4),
+            mode: AddressGeneratorMode::PostincrementR(Register::A(1)),
+        };
+        assert_eq!(operand.to_string(), "*A4++[A1]");
+    }
+}
@@ -1,8 +1,6 @@
-use std::{
-    collections::HashMap,
-    io::{Error, ErrorKind, Result},
-};
+use std::collections::HashMap;
 
+use crate::error::{DecodeError, Result};
 use crate::instruction::{
     ConditionalOperation, Unit,
     register::{ControlRegister, Register},
@@ -20,25 +18,30 @@ pub fn parse(
             ParsingInstruction::Match { size, value } => {
                 let masked_value = read_u32(&mut temp_opcode, *size);
                 if masked_value != *value {
-                    return Err(Error::new(
-                        ErrorKind::InvalidInput,
-                        format!(
-                            "Opcode does not match instruction format (got {masked_value:b} instead of {value:b})"
-                        ),
-                    ));
+                    return Err(DecodeError::OpcodeMismatch {
+                        opcode,
+                        reason: format!("got {masked_value:b} instead of {value:b}"),
+                    });
                 }
             }
             ParsingInstruction::MatchMultiple { size, values } => {
                 let masked_value = read_u32(&mut temp_opcode, *size);
-                for value in values {
-                    if masked_value == *value {
-                        continue;
-                    }
+                if !values.contains(&masked_value) {
+                    return Err(DecodeError::OpcodeMismatch {
+                        opcode,
+                        reason: format!("got {masked_value:b}"),
+                    });
+                }
+            }
+            ParsingInstruction::Reserved { size } => {
+                let masked_value = read_u32(&mut temp_opcode, *size);
+                if masked_value != 0 {
+                    return Err(DecodeError::ReservedField {
+                        opcode,
+                        field: String::from("reserved"),
+                        value: masked_value,
+                    });
                 }
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("Opcode does not match instruction format (got {masked_value:b})"),
-                ));
             }
             ParsingInstruction::Bit { name } => {
                 resulting_map.insert(
@@ -49,12 +52,10 @@ pub fn parse(
             ParsingInstruction::BitMatch { name, value } => {
                 let read_value = read_bool(&mut temp_opcode);
                 if read_value != *value {
-                    return Err(Error::new(
-                        ErrorKind::InvalidInput,
-                        format!(
-                            "Opcode does not match instruction format ({name} is {read_value} instead of {value})"
-                        ),
-                    ));
+                    return Err(DecodeError::OpcodeMismatch {
+                        opcode,
+                        reason: format!("{name} is {read_value} instead of {value}"),
+                    });
                 }
                 resulting_map.insert(name.clone(), ParsedVariable::Bool(read_value));
             }
@@ -67,14 +68,22 @@ pub fn parse(
             }
             ParsingInstruction::Unsigned { size, name } => {
                 let value = read_u32(&mut temp_opcode, *size);
-                let parsed_variable = {
-                    if *size > 8 {
-                        ParsedVariable::U32(value)
-                    } else {
-                        ParsedVariable::U8(value as u8)
-                    }
-                };
-                resulting_map.insert(name.clone(), parsed_variable);
+                resulting_map.insert(name.clone(), ParsedVariable::from_unsigned(value, *size));
+            }
+            ParsingInstruction::UnsignedRange {
+                size,
+                name,
+                min,
+                max,
+            } => {
+                let value = read_u32(&mut temp_opcode, *size);
+                if value < *min || value > *max {
+                    return Err(DecodeError::OpcodeMismatch {
+                        opcode,
+                        reason: format!("{name} is {value}, outside of {min}..={max}"),
+                    });
+                }
+                resulting_map.insert(name.clone(), ParsedVariable::from_unsigned(value, *size));
             }
             ParsingInstruction::Signed { size, name } => {
                 let value = read_i32(&mut temp_opcode, *size);
@@ -112,9 +121,11 @@ pub fn parse(
                     }
                 };
                 let Some(value) = ControlRegister::from(low_bits, high_bits) else {
-                    return Err(Error::other(format!(
-                        "Invalid Control Register values (got crhi crlo {high_bits:b} {low_bits:b})"
-                    )));
+                    return Err(DecodeError::ReservedField {
+                        opcode,
+                        field: name.clone(),
+                        value: ((high_bits as u32) << size) | low_bits as u32,
+                    });
                 };
                 resulting_map.insert(name.clone(), ParsedVariable::ControlRegister(value));
             }
@@ -123,7 +134,13 @@ pub fn parse(
                     0 => Unit::L,
                     1 => Unit::S,
                     2 => Unit::D,
-                    num => return Err(Error::other(format!("Invalid LSDUnit (got {num})"))),
+                    num => {
+                        return Err(DecodeError::ReservedField {
+                            opcode,
+                            field: name.clone(),
+                            value: num,
+                        });
+                    }
                 };
                 resulting_map.insert(name.clone(), ParsedVariable::Unit(unit));
             }
@@ -151,6 +168,10 @@ pub enum ParsingInstruction {
         size: usize,
         values: Vec<u32>,
     },
+    /// Reserved bits, which have to be cleared.
+    Reserved {
+        size: usize,
+    },
     Bit {
         name: String,
     },
@@ -166,6 +187,13 @@ pub enum ParsingInstruction {
         size: usize,
         name: String,
     },
+    /// Unsigned value which has to be within ``min..=max``.
+    UnsignedRange {
+        size: usize,
+        name: String,
+        min: u32,
+        max: u32,
+    },
     Signed {
         size: usize,
         name: String,
@@ -195,7 +223,107 @@ pub enum ParsingInstruction {
     },
 }
 
-#[derive(Clone)]
+impl ParsingInstruction {
+    /// Number of opcode bits consumed by this parsing instruction.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Match { size, .. }
+            | Self::MatchMultiple { size, .. }
+            | Self::Reserved { size }
+            | Self::BitArray { size, .. }
+            | Self::Unsigned { size, .. }
+            | Self::UnsignedRange { size, .. }
+            | Self::Signed { size, .. }
+            | Self::Register { size, .. }
+            | Self::RegisterPair { size, .. }
+            | Self::RegisterCrosspath { size, .. }
+            | Self::ControlRegister { size, .. } => *size,
+            Self::Bit { .. } | Self::BitMatch { .. } => 1,
+            Self::LSDUnit { .. } => 2,
+            Self::ConditionalOperation { .. } => 4,
+        }
+    }
+}
+
+/// A named opcode layout from which an instruction can be decoded.
+#[derive(Debug)]
+pub struct InstructionFormat {
+    pub name: &'static str,
+    pub parsing_instructions: Vec<ParsingInstruction>,
+}
+
+impl InstructionFormat {
+    pub fn new(name: &'static str, parsing_instructions: Vec<ParsingInstruction>) -> Self {
+        Self {
+            name,
+            parsing_instructions,
+        }
+    }
+
+    /// Returns every combination of fixed opcode bits accepted by the format,
+    /// as `(mask, value)` pairs.
+    ///
+    /// [ParsingInstruction::Match], [ParsingInstruction::BitMatch] and
+    /// [ParsingInstruction::Reserved] fix bits, while value sets and ranges
+    /// produce a separate pair for every block of values they accept.
+    pub fn fixed_bits(&self) -> Vec<(u32, u32)> {
+        let mut patterns = vec![(0u32, 0u32)];
+        let mut position = 0;
+        for instruction in &self.parsing_instructions {
+            let size = instruction.size();
+            let field_patterns = match instruction {
+                ParsingInstruction::Match { value, .. } => vec![(create_mask(size), *value)],
+                ParsingInstruction::Reserved { .. } => vec![(create_mask(size), 0)],
+                ParsingInstruction::BitMatch { value, .. } => vec![(1, *value as u32)],
+                ParsingInstruction::MatchMultiple { values, .. } => values
+                    .iter()
+                    .map(|value| (create_mask(size), *value))
+                    .collect(),
+                ParsingInstruction::UnsignedRange { min, max, .. } => {
+                    range_patterns(*min, *max, size)
+                }
+                _ => {
+                    position += size;
+                    continue;
+                }
+            };
+            patterns = patterns
+                .iter()
+                .flat_map(|(mask, value)| {
+                    field_patterns.iter().map(move |(field_mask, field_value)| {
+                        (
+                            mask | field_mask << position,
+                            value | field_value << position,
+                        )
+                    })
+                })
+                .collect();
+            position += size;
+        }
+        patterns
+    }
+}
+
+/// Splits ``min..=max`` into aligned blocks, returned as `(mask, value)` pairs
+/// of a field with the given size.
+fn range_patterns(min: u32, max: u32, size: usize) -> Vec<(u32, u32)> {
+    let field_mask = create_mask(size) as u64;
+    let max = (max as u64).min(field_mask);
+    let mut value = min as u64;
+    let mut patterns = Vec::new();
+    while value <= max {
+        let mut block_bits = value.trailing_zeros().min(size as u32);
+        while value + (1 << block_bits) - 1 > max {
+            block_bits -= 1;
+        }
+        let block_mask = (1u64 << block_bits) - 1;
+        patterns.push(((field_mask & !block_mask) as u32, value as u32));
+        value += 1 << block_bits;
+    }
+    patterns
+}
+
+#[derive(Clone, Debug)]
 pub enum ParsedVariable {
     Bool(bool),
     BoolVec(Vec<bool>),
@@ -209,11 +337,19 @@ pub enum ParsedVariable {
 }
 
 impl ParsedVariable {
+    fn from_unsigned(value: u32, size: usize) -> Self {
+        if size > 8 {
+            ParsedVariable::U32(value)
+        } else {
+            ParsedVariable::U8(value as u8)
+        }
+    }
+
     pub fn get_bool(&self) -> Result<bool> {
         if let ParsedVariable::Bool(value) = self {
             Ok(*value)
         } else {
-            Err(Error::other("Not a Bool variable"))
+            Err(DecodeError::VariableType { expected: "Bool" })
         }
     }
 
@@ -221,7 +357,9 @@ impl ParsedVariable {
         if let ParsedVariable::BoolVec(value) = self {
             Ok(value.clone())
         } else {
-            Err(Error::other("Not a BoolVec variable"))
+            Err(DecodeError::VariableType {
+                expected: "BoolVec",
+            })
         }
     }
 
@@ -231,7 +369,7 @@ impl ParsedVariable {
         } else if let ParsedVariable::U8(value) = self {
             Ok(*value as u32)
         } else {
-            Err(Error::other("Not a U32 variable"))
+            Err(DecodeError::VariableType { expected: "U32" })
         }
     }
 
@@ -243,7 +381,7 @@ impl ParsedVariable {
         } else if let ParsedVariable::U8(value) = self {
             Ok(u8::cast_signed(*value) as i32)
         } else {
-            Err(Error::other("Not a U32 variable"))
+            Err(DecodeError::VariableType { expected: "I32" })
         }
     }
 
@@ -253,7 +391,7 @@ impl ParsedVariable {
         } else if let ParsedVariable::U32(value) = self {
             Ok(*value as u8)
         } else {
-            Err(Error::other("Not a U8 variable"))
+            Err(DecodeError::VariableType { expected: "U8" })
         }
     }
 
@@ -261,7 +399,9 @@ impl ParsedVariable {
         if let ParsedVariable::Register(value) = self {
             Ok(*value)
         } else {
-            Err(Error::other("Not a Register variable"))
+            Err(DecodeError::VariableType {
+                expected: "Register",
+            })
         }
     }
 
@@ -269,7 +409,9 @@ impl ParsedVariable {
         if let ParsedVariable::ControlRegister(value) = self {
             Ok(*value)
         } else {
-            Err(Error::other("Not a Control Register variable"))
+            Err(DecodeError::VariableType {
+                expected: "Control Register",
+            })
         }
     }
 
@@ -277,7 +419,7 @@ impl ParsedVariable {
         if let ParsedVariable::Unit(value) = self {
             Ok(*value)
         } else {
-            Err(Error::other("Not a Unit variable"))
+            Err(DecodeError::VariableType { expected: "Unit" })
         }
     }
 
@@ -285,45 +427,38 @@ impl ParsedVariable {
         if let ParsedVariable::ConditionalOperation(value) = self {
             Ok(*value)
         } else {
-            Err(Error::other("Not a Conditional Operation variable"))
+            Err(DecodeError::VariableType {
+                expected: "Conditional Operation",
+            })
         }
     }
 
     pub fn try_get<'a>(hashmap: &'a HashMap<String, Self>, name: &str) -> Result<&'a Self> {
         let Some(value) = hashmap.get(name) else {
-            return Err(Error::other("Parsing error"));
+            return Err(DecodeError::MissingVariable {
+                name: String::from(name),
+            });
         };
         Ok(value)
     }
 }
 
 fn read_bool(opcode: &mut u32) -> bool {
-    let value = if *opcode & 1 == 1 { true } else { false };
+    let value = *opcode & 1 == 1;
     *opcode >>= 1;
     value
 }
 
 fn read_i32(opcode: &mut u32, size: usize) -> i32 {
-    let mask = create_mask(size);
-    let mut value_u32 = *opcode & mask;
-    *opcode >>= size;
-    let sign_bit_mask = 1 << (size - 1);
-    let value = {
-        if value_u32 & sign_bit_mask == sign_bit_mask {
-            value_u32 ^= mask;
-            value_u32 += 1;
-            -(value_u32 as i32)
-        } else {
-            value_u32 as i32
-        }
-    };
-    value
+    let value_u32 = read_u32(opcode, size);
+    let unused_bits = 32 - size as u32;
+    ((value_u32 << unused_bits) as i32) >> unused_bits
 }
 
 fn read_u32(opcode: &mut u32, size: usize) -> u32 {
     let mask = create_mask(size);
     let value = *opcode & mask;
-    *opcode >>= size;
+    *opcode = opcode.checked_shr(size as u32).unwrap_or(0);
     value
 }
 
@@ -335,3 +470,336 @@ fn create_mask(size: usize) -> u32 {
     }
     mask
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(name: &str) -> String {
+        String::from(name)
+    }
+
+    #[test]
+    fn match_checks_value() {
+        let format = [ParsingInstruction::Match {
+            size: 4,
+            value: 0b1010,
+        }];
+        assert!(parse(0b1010, &format).is_ok());
+        assert!(matches!(
+            parse(0b1011, &format),
+            Err(DecodeError::OpcodeMismatch { opcode: 0b1011, .. })
+        ));
+    }
+
+    #[test]
+    fn match_multiple_accepts_every_listed_value() {
+        let format = [ParsingInstruction::MatchMultiple {
+            size: 7,
+            values: vec![0x2, 0x7E],
+        }];
+        assert!(parse(0x2, &format).is_ok());
+        assert!(parse(0x7E, &format).is_ok());
+        assert!(parse(0x3, &format).is_err());
+    }
+
+    #[test]
+    fn reserved_requires_cleared_bits() {
+        let format = [
+            ParsingInstruction::Bit { name: name("a") },
+            ParsingInstruction::Reserved { size: 3 },
+        ];
+        assert!(parse(0b0001, &format).is_ok());
+        assert_eq!(
+            parse(0b0100, &format).unwrap_err(),
+            DecodeError::ReservedField {
+                opcode: 0b0100,
+                field: String::from("reserved"),
+                value: 0b010,
+            }
+        );
+    }
+
+    #[test]
+    fn bit_reads_single_bit() {
+        let format = [
+            ParsingInstruction::Bit { name: name("a") },
+            ParsingInstruction::Bit { name: name("b") },
+        ];
+        let parsed = parse(0b10, &format).unwrap();
+        assert!(!parsed["a"].get_bool().unwrap());
+        assert!(parsed["b"].get_bool().unwrap());
+    }
+
+    #[test]
+    fn bit_match_checks_and_stores_bit() {
+        let format = [ParsingInstruction::BitMatch {
+            name: name("s"),
+            value: true,
+        }];
+        assert!(parse(1, &format).unwrap()["s"].get_bool().unwrap());
+        assert!(parse(0, &format).is_err());
+    }
+
+    #[test]
+    fn bit_array_reads_from_least_significant_bit() {
+        let format = [ParsingInstruction::BitArray {
+            size: 4,
+            name: name("layout"),
+        }];
+        let parsed = parse(0b0110, &format).unwrap();
+        assert_eq!(
+            parsed["layout"].get_bool_vec().unwrap(),
+            vec![false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn unsigned_reads_field() {
+        let format = [
+            ParsingInstruction::Unsigned {
+                size: 3,
+                name: name("small"),
+            },
+            ParsingInstruction::Unsigned {
+                size: 16,
+                name: name("large"),
+            },
+        ];
+        let parsed = parse(0xBEEF << 3 | 0b101, &format).unwrap();
+        assert_eq!(parsed["small"].get_u8().unwrap(), 0b101);
+        assert_eq!(parsed["large"].get_u32().unwrap(), 0xBEEF);
+    }
+
+    #[test]
+    fn unsigned_range_checks_bounds() {
+        let format = [ParsingInstruction::UnsignedRange {
+            size: 3,
+            name: name("nop"),
+            min: 1,
+            max: 5,
+        }];
+        assert!(parse(0, &format).is_err());
+        assert_eq!(parse(1, &format).unwrap()["nop"].get_u8().unwrap(), 1);
+        assert_eq!(parse(5, &format).unwrap()["nop"].get_u8().unwrap(), 5);
+        assert!(parse(6, &format).is_err());
+    }
+
+    #[test]
+    fn signed_sign_extends() {
+        let format = [ParsingInstruction::Signed {
+            size: 7,
+            name: name("cst"),
+        }];
+        assert_eq!(parse(0x3F, &format).unwrap()["cst"].get_i32().unwrap(), 63);
+        assert_eq!(parse(0x40, &format).unwrap()["cst"].get_i32().unwrap(), -64);
+        assert_eq!(parse(0x7F, &format).unwrap()["cst"].get_i32().unwrap(), -1);
+    }
+
+    #[test]
+    fn read_i32_sign_extends_every_width() {
+        for size in 1..=32 {
+            let mask = create_mask(size);
+            let max = (mask >> 1) as i32;
+            let min = -max - 1;
+            let cases = [
+                (0, 0),
+                (mask >> 1, max),
+                (mask ^ (mask >> 1), min),
+                (mask, -1),
+            ];
+            for (field, expected) in cases {
+                let mut opcode = field;
+                assert_eq!(read_i32(&mut opcode, size), expected, "size {size}");
+                assert_eq!(opcode, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn read_i32_consumes_field() {
+        let mut opcode = 0xABCD_0003;
+        assert_eq!(read_i32(&mut opcode, 16), 3);
+        assert_eq!(opcode, 0xABCD);
+    }
+
+    #[test]
+    fn register_uses_side_bit() {
+        let format = [
+            ParsingInstruction::Bit { name: name("s") },
+            ParsingInstruction::Register {
+                size: 5,
+                name: name("dst"),
+            },
+        ];
+        let parsed = parse(12 << 1 | 1, &format).unwrap();
+        assert_eq!(parsed["dst"].get_register().unwrap(), Register::B(12));
+        let parsed = parse(12 << 1, &format).unwrap();
+        assert_eq!(parsed["dst"].get_register().unwrap(), Register::A(12));
+    }
+
+    #[test]
+    fn register_requires_side_bit() {
+        let format = [ParsingInstruction::Register {
+            size: 5,
+            name: name("dst"),
+        }];
+        assert_eq!(
+            parse(0, &format).unwrap_err(),
+            DecodeError::MissingVariable {
+                name: String::from("s")
+            }
+        );
+    }
+
+    #[test]
+    fn register_crosspath_flips_side() {
+        let format = [
+            ParsingInstruction::Bit { name: name("s") },
+            ParsingInstruction::Bit { name: name("x") },
+            ParsingInstruction::RegisterCrosspath {
+                size: 5,
+                name: name("src"),
+            },
+        ];
+        let parsed = parse(3 << 2 | 0b11, &format).unwrap();
+        assert_eq!(parsed["src"].get_register().unwrap(), Register::A(3));
+        let parsed = parse(3 << 2 | 0b01, &format).unwrap();
+        assert_eq!(parsed["src"].get_register().unwrap(), Register::B(3));
+    }
+
+    #[test]
+    fn register_pair_reads_even_odd_pair() {
+        let format = [
+            ParsingInstruction::Bit { name: name("s") },
+            ParsingInstruction::RegisterPair {
+                size: 5,
+                name: name("dst"),
+            },
+        ];
+        let parsed = parse(5 << 1, &format).unwrap();
+        assert_eq!(parsed["dst"].get_register().unwrap(), Register::APair(5, 4));
+    }
+
+    #[test]
+    fn control_register_uses_high_bits() {
+        let format = [
+            ParsingInstruction::Unsigned {
+                size: 5,
+                name: name("crhi"),
+            },
+            ParsingInstruction::ControlRegister {
+                size: 5,
+                name: name("crlo"),
+            },
+        ];
+        let parsed = parse(0b00010 << 5, &format).unwrap();
+        assert_eq!(
+            parsed["crlo"].get_control_register().unwrap(),
+            ControlRegister::IFR
+        );
+        let parsed = parse(0b00010 << 5 | 0b00001, &format).unwrap();
+        assert_eq!(
+            parsed["crlo"].get_control_register().unwrap(),
+            ControlRegister::ISR
+        );
+        assert!(matches!(
+            parse(0b01000 << 5, &format),
+            Err(DecodeError::ReservedField { value: 0b01000, .. })
+        ));
+    }
+
+    #[test]
+    fn lsd_unit_reads_unit() {
+        let format = [ParsingInstruction::LSDUnit { name: name("unit") }];
+        assert_eq!(
+            parse(0, &format).unwrap()["unit"].get_unit().unwrap(),
+            Unit::L
+        );
+        assert_eq!(
+            parse(1, &format).unwrap()["unit"].get_unit().unwrap(),
+            Unit::S
+        );
+        assert_eq!(
+            parse(2, &format).unwrap()["unit"].get_unit().unwrap(),
+            Unit::D
+        );
+        assert!(matches!(
+            parse(3, &format),
+            Err(DecodeError::ReservedField { value: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn conditional_operation_reads_z_and_creg() {
+        let format = [ParsingInstruction::ConditionalOperation { name: name("cond") }];
+        let parsed = parse(0b0011, &format).unwrap();
+        assert_eq!(
+            parsed["cond"].get_conditional_operation().unwrap(),
+            Some(ConditionalOperation::Zero(Register::B(0)))
+        );
+        let parsed = parse(0b1000, &format).unwrap();
+        assert_eq!(
+            parsed["cond"].get_conditional_operation().unwrap(),
+            Some(ConditionalOperation::NonZero(Register::A(1)))
+        );
+        let parsed = parse(0b1111, &format).unwrap();
+        assert_eq!(
+            parsed["cond"].get_conditional_operation().unwrap(),
+            Some(ConditionalOperation::ReservedHigh)
+        );
+    }
+
+    #[test]
+    fn fixed_bits_skip_variable_fields() {
+        let format = InstructionFormat::new(
+            "test",
+            vec![
+                ParsingInstruction::ConditionalOperation { name: name("cond") },
+                ParsingInstruction::LSDUnit { name: name("unit") },
+                ParsingInstruction::Match {
+                    size: 2,
+                    value: 0b11,
+                },
+            ],
+        );
+        assert_eq!(format.fixed_bits(), vec![(0b11 << 6, 0b11 << 6)]);
+    }
+
+    #[test]
+    fn fixed_bits_accept_exactly_the_parsed_opcodes() {
+        let format = InstructionFormat::new(
+            "test",
+            vec![
+                ParsingInstruction::BitMatch {
+                    name: name("s"),
+                    value: true,
+                },
+                ParsingInstruction::UnsignedRange {
+                    size: 4,
+                    name: name("range"),
+                    min: 3,
+                    max: 12,
+                },
+                ParsingInstruction::MatchMultiple {
+                    size: 2,
+                    values: vec![0, 2],
+                },
+                ParsingInstruction::Reserved { size: 1 },
+                ParsingInstruction::Unsigned {
+                    size: 2,
+                    name: name("free"),
+                },
+            ],
+        );
+        let patterns = format.fixed_bits();
+        for opcode in 0..1 << 10 {
+            let matches_pattern = patterns.iter().any(|(mask, value)| opcode & mask == *value);
+            assert_eq!(
+                matches_pattern,
+                parse(opcode, &format.parsing_instructions).is_ok(),
+                "opcode {opcode:b}"
+            );
+        }
+    }
+}
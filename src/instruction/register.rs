@@ -1,9 +1,10 @@
 use std::{
+    collections::HashSet,
     fmt::Display,
     ops::{AddAssign, Not},
 };
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum Register {
     A(u8),
     APair(u8, u8),
@@ -13,7 +14,7 @@ pub enum Register {
 
 impl Register {
     pub fn from(value: u8, side: bool) -> Self {
-        if side == false {
+        if !side {
             Self::A(value)
         } else {
             Self::B(value)
@@ -23,7 +24,7 @@ impl Register {
     pub fn from_pair(value: u8, side: bool) -> Self {
         let value2 = value - value % 2;
         let value1 = value2 + 1;
-        if side == false {
+        if !side {
             Self::APair(value1, value2)
         } else {
             Self::BPair(value1, value2)
@@ -38,6 +39,15 @@ impl Register {
             Self::BPair(_, _) => true,
         }
     }
+
+    /// Returns the individual registers of a pair, or the register itself.
+    pub fn split(&self) -> Vec<Register> {
+        match *self {
+            Self::APair(num1, num2) => vec![Self::A(num1), Self::A(num2)],
+            Self::BPair(num1, num2) => vec![Self::B(num1), Self::B(num2)],
+            register => vec![register],
+        }
+    }
 }
 
 impl Display for Register {
@@ -76,7 +86,7 @@ impl Not for Register {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum ControlRegister {
     /// Addressing mode register.
     AMR,
@@ -204,6 +214,7 @@ impl Display for ControlRegister {
     }
 }
 
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum RegisterFile {
     GeneralPurpose(Register),
     Control(ControlRegister),
@@ -219,6 +230,12 @@ impl RegisterFile {
     }
 }
 
+impl From<Register> for RegisterFile {
+    fn from(register: Register) -> Self {
+        Self::GeneralPurpose(register)
+    }
+}
+
 impl Display for RegisterFile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -227,3 +244,20 @@ impl Display for RegisterFile {
         }
     }
 }
+
+/// Collects registers into a set, splitting register pairs
+/// into their individual registers.
+pub fn register_set(registers: impl IntoIterator<Item = RegisterFile>) -> HashSet<RegisterFile> {
+    let mut set = HashSet::new();
+    for register in registers {
+        match register {
+            RegisterFile::GeneralPurpose(register) => {
+                set.extend(register.split().into_iter().map(RegisterFile::from))
+            }
+            RegisterFile::Control(_) => {
+                set.insert(register);
+            }
+        }
+    }
+    set
+}
@@ -0,0 +1,61 @@
+use crate::instruction::mnemonic::Mnemonic;
+
+/// Pipeline timing of an instruction.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Timing {
+    /// Number of cycles after the first execute phase (E1) before the
+    /// results of the instruction can be read.
+    pub delay_slots: u8,
+    /// Number of cycles the functional unit is busy before it can
+    /// accept another instruction.
+    pub functional_unit_latency: u8,
+    /// Number of cycles of ``NOP`` issued by the instruction itself
+    /// (``NOP n``, ``BNOP``, ``CALLP``).
+    pub nop_cycles: u8,
+}
+
+impl Timing {
+    /// Timing of an instruction executed by a functional unit in a single cycle.
+    pub const fn single_cycle(delay_slots: u8) -> Self {
+        Self {
+            delay_slots,
+            functional_unit_latency: 1,
+            nop_cycles: 0,
+        }
+    }
+
+    /// Returns the execute phase (counted from E1 = 1) in which
+    /// the results are written.
+    pub fn writeback_cycle(&self) -> u8 {
+        self.delay_slots + 1
+    }
+
+    /// Returns the timing with the given number of ``NOP`` cycles.
+    pub fn with_nop_cycles(self, nop_cycles: u8) -> Self {
+        Self { nop_cycles, ..self }
+    }
+}
+
+impl Mnemonic {
+    /// Returns the timing of the instruction, without any ``NOP`` cycles.
+    pub fn timing(&self) -> Timing {
+        match self {
+            Self::B | Self::BNOP | Self::CALLP => Timing::single_cycle(5),
+            Self::LDB
+            | Self::LDBU
+            | Self::LDH
+            | Self::LDHU
+            | Self::LDW
+            | Self::LDNW
+            | Self::LDDW
+            | Self::LDNDW => Timing::single_cycle(4),
+            Self::STB | Self::STH | Self::STW | Self::STNW | Self::STDW | Self::STNDW => {
+                Timing::single_cycle(0)
+            }
+            Self::MVD => Timing::single_cycle(3),
+            Self::MV | Self::MVC | Self::MVK | Self::MVKH | Self::ZERO => Timing::single_cycle(0),
+            Self::ADDKPC => Timing::single_cycle(0),
+            Self::NOP | Self::IDLE | Self::FPHEAD | Self::INVALID => Timing::default(),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::instruction::C6000Instruction;
+use crate::symbols::Symbolizer;
+
+/// Quotes the text as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            character if character.is_control() => {
+                quoted += &format!("\\u{:04x}", character as u32)
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Returns a JSON object describing the instruction: its address, opcode,
+/// compact and parallel flags, condition, mnemonic, unit, operands and label.
+///
+/// Missing conditions, units and labels are ``null``.
+pub fn format_json(
+    instruction: &dyn C6000Instruction,
+    address: u32,
+    label: Option<&str>,
+    symbolizer: &Symbolizer,
+) -> String {
+    let optional = |value: Option<String>| {
+        value.map_or_else(|| String::from("null"), |value| json_string(&value))
+    };
+    let operands: Vec<String> = instruction
+        .operand_list()
+        .iter()
+        .map(|operand| json_string(&symbolizer.operand(address, operand)))
+        .collect();
+    format!(
+        "{{\"address\":{address},\"opcode\":{},\"compact\":{},\"parallel\":{},\
+         \"condition\":{},\"mnemonic\":{},\"unit\":{},\"operands\":[{}],\"label\":{}}}",
+        instruction.opcode(),
+        instruction.is_compact(),
+        instruction.is_parallel(),
+        optional(
+            instruction
+                .conditional_operation()
+                .filter(|operation| operation.register().is_some())
+                .map(|operation| operation.to_string())
+        ),
+        json_string(&instruction.mnemonic().to_string()),
+        optional(
+            instruction
+                .unit()
+                .map(|unit| unit.to_sided_string(instruction.side().unwrap_or_default()))
+        ),
+        operands.join(","),
+        optional(label.map(String::from)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Disassembler, symbols::SymbolTable};
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("B3"), "\"B3\"");
+        assert_eq!(json_string("a \"b\" \\c"), "\"a \\\"b\\\" \\\\c\"");
+        assert_eq!(json_string("\n\t\u{1}"), "\"\\u000a\\u0009\\u0001\"");
+    }
+
+    #[test]
+    fn json_records_describe_instructions() {
+        // MVK.S1 0x1234, A3 with its p-bit set, followed by MVK.S2 0x1234, B3
+        let words: [u32; 2] = [0x01891A29, 0x01891A2A];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let instructions: Vec<_> = Disassembler::from_slice(&data, 0x1000)
+            .map(|item| item.unwrap())
+            .collect();
+        let symbols = SymbolTable::new();
+        let symbolizer = Symbolizer::new(&symbols, &instructions);
+        let (address, instruction) = &instructions[1];
+        assert_eq!(
+            format_json(instruction.as_ref(), *address, Some("a\"b"), &symbolizer),
+            "{\"address\":4100,\"opcode\":25762346,\"compact\":false,\"parallel\":true,\
+             \"condition\":null,\"mnemonic\":\"MVK\",\"unit\":\"S2\",\
+             \"operands\":[\"0x1234\",\"B3\"],\"label\":\"a\\\"b\"}"
+        );
+        let (address, instruction) = &instructions[0];
+        assert!(
+            format_json(instruction.as_ref(), *address, None, &symbolizer)
+                .contains("\"parallel\":false,")
+        );
+    }
+}
@@ -1,67 +1,88 @@
-use std::io::{Error, ErrorKind, Result};
+use std::{
+    collections::VecDeque,
+    io::{self, Cursor, ErrorKind, Read},
+};
 
+use crate::error::{DecodeError, Result};
 use crate::instruction::{
-    C6000Instruction, InstructionInput,
-    branching::BranchInstruction,
+    C6000Instruction, ConditionalOperation, InstructionInput,
+    dispatch::{compact_instruction_table, instruction_table},
     fphead::CompactInstructionHeader,
     invalid::InvalidInstruction,
-    memory::MemoryInstruction,
-    moving::{MoveConstantInstruction, MoveRegisterInstruction},
-    nop::NOPInstruction,
 };
 
+pub mod cfg;
+pub mod csv;
+pub mod dot;
+pub mod error;
+pub mod flow;
+pub mod functions;
+pub mod input;
 pub mod instruction;
+pub mod json;
+pub mod packet;
+pub mod symbols;
 
 /// Reads a compact 16-bit instruction and returns a result containing a
 /// struct with the [C6000Instruction] trait.
+///
+/// If the opcode cannot be decoded, an [InvalidInstruction] holding the
+/// [DecodeError] is returned instead.
 pub fn read_compact_instruction(input: InstructionInput) -> Result<Box<dyn C6000Instruction>> {
-    if let Ok(instruction) = MoveConstantInstruction::new_compact(&input) {
-        return Ok(Box::new(instruction));
-    }
-
-    if let Ok(instruction) = MoveRegisterInstruction::new_compact(&input) {
-        return Ok(Box::new(instruction));
-    }
-
-    if let Ok(instruction) = BranchInstruction::new_compact(&input) {
-        return Ok(Box::new(instruction));
-    }
-
-    if let Ok(instruction) = MemoryInstruction::new_compact(&input) {
-        return Ok(Box::new(instruction));
-    }
-
-    if let Ok(instruction) = NOPInstruction::new_compact(&input) {
-        return Ok(Box::new(instruction));
-    }
+    let error = match compact_instruction_table().decode(&input) {
+        Some(Ok(instruction)) => return Ok(instruction),
+        Some(Err(error)) => error,
+        None => DecodeError::UnknownOpcode {
+            opcode: input.opcode,
+        },
+    };
 
-    Ok(Box::new(InvalidInstruction::new_compact(&input)?))
+    Ok(Box::new(
+        InvalidInstruction::new_compact(&input)?.with_error(error),
+    ))
 }
 
 /// Reads a 32-bit instruction and returns a result containing a
 /// struct with the [C6000Instruction] trait.
+///
+/// If the opcode cannot be decoded, an [InvalidInstruction] holding the
+/// [DecodeError] is returned instead.
 pub fn read_instruction(input: InstructionInput) -> Result<Box<dyn C6000Instruction>> {
-    if let Ok(instruction) = MoveConstantInstruction::new(&input) {
-        return Ok(Box::new(instruction));
-    }
+    let error = match instruction_table().decode(&input) {
+        Some(Ok(instruction)) => return Ok(instruction),
+        Some(Err(error)) => error,
+        None => DecodeError::UnknownOpcode {
+            opcode: input.opcode,
+        },
+    };
 
-    if let Ok(instruction) = MoveRegisterInstruction::new(&input) {
-        return Ok(Box::new(instruction));
-    }
+    Ok(Box::new(InvalidInstruction::new(&input)?.with_error(error)))
+}
 
-    if let Ok(instruction) = BranchInstruction::new(&input) {
-        return Ok(Box::new(instruction));
-    }
+/// Byte order of the instructions in memory.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
 
-    if let Ok(instruction) = MemoryInstruction::new(&input) {
-        return Ok(Box::new(instruction));
+impl Endianness {
+    /// Reads a 32-bit word from its bytes, in address order.
+    pub fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
     }
 
-    if let Ok(instruction) = NOPInstruction::new(&input) {
-        return Ok(Box::new(instruction));
+    /// Reads a 16-bit halfword from its bytes, in address order.
+    pub fn read_u16(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
     }
-
-    Ok(Box::new(InvalidInstruction::new(&input)?))
 }
 
 /// Size of a regular instruction in bytes
@@ -71,13 +92,18 @@ pub const COMPACT_INSTRUCTION_SIZE: usize = 2;
 /// Size of an FP (Fetch Packet) in bytes
 pub const PACKET_SIZE: usize = 8 * INSTRUCTION_SIZE;
 
+/// Reads a fetch packet ending with a compact instruction header.
+///
+/// Compact instructions are read in address order, so in big-endian mode
+/// the upper halfword of a word comes first.
 pub fn read_packet(
     packet: [u8; PACKET_SIZE],
     address: u32,
+    endianness: Endianness,
 ) -> Result<Vec<Box<dyn C6000Instruction>>> {
     let mut vec: Vec<Box<dyn C6000Instruction>> = vec![];
     let Ok(fphead) = CompactInstructionHeader::new(&InstructionInput {
-        opcode: u32::from_le_bytes([
+        opcode: endianness.read_u32([
             packet[PACKET_SIZE - 4],
             packet[PACKET_SIZE - 3],
             packet[PACKET_SIZE - 2],
@@ -86,10 +112,7 @@ pub fn read_packet(
         fphead: None,
         pce1_address: address,
     }) else {
-        return Err(Error::new(
-            ErrorKind::InvalidInput,
-            "Not a fetch packet, use read_instruction instead.",
-        ));
+        return Err(DecodeError::NotFetchPacket { address });
     };
 
     let mut index = 0;
@@ -98,16 +121,17 @@ pub fn read_packet(
         let instruction = {
             if fphead.layout[index / 4] {
                 let mut compact_instruction = read_compact_instruction(InstructionInput {
-                    opcode: u16::from_le_bytes([packet[index], packet[index + 1]]) as u32,
+                    opcode: endianness.read_u16([packet[index], packet[index + 1]]) as u32,
                     fphead: Some(fphead.clone()),
                     pce1_address: address,
                 })?;
                 compact_instruction.set_parallel(previous_p_bit);
                 previous_p_bit = fphead.compact_p_bits[index / 2];
+                compact_instruction.instruction_data_mut().p_bit = previous_p_bit;
                 compact_instruction
             } else {
                 let mut instruction = read_instruction(InstructionInput {
-                    opcode: u32::from_le_bytes([
+                    opcode: endianness.read_u32([
                         packet[index],
                         packet[index + 1],
                         packet[index + 2],
@@ -123,10 +147,9 @@ pub fn read_packet(
         };
 
         if instruction.as_any().is::<CompactInstructionHeader>() {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Compact instruction header found in unusual place",
-            ));
+            return Err(DecodeError::UnexpectedHeader {
+                address: address + index as u32,
+            });
         }
 
         if instruction.is_compact() {
@@ -141,3 +164,316 @@ pub fn read_packet(
 
     Ok(vec)
 }
+
+/// Formats a line of the listing for the instruction at the address,
+/// with its operands already formatted.
+pub fn format_instruction(
+    instruction: &dyn C6000Instruction,
+    address: u32,
+    operands: String,
+) -> String {
+    format!(
+        "0x{address:08X}: {:<12}{:<4}{:<6} {:<12} {}",
+        format!("{:X}", instruction.opcode()),
+        {
+            if instruction.is_parallel() {
+                String::from("||")
+            } else {
+                String::new()
+            }
+        },
+        {
+            if let Some(operation) = instruction.conditional_operation()
+                && operation != ConditionalOperation::ReservedLow
+                && operation != ConditionalOperation::ReservedHigh
+            {
+                format!("[{:>3}]", operation.to_string())
+            } else {
+                String::new()
+            }
+        },
+        instruction.instruction(),
+        operands
+    )
+}
+
+/// Iterator decoding instructions from a byte source, yielding every
+/// instruction along with its address.
+///
+/// Fetch packets are aligned to 32-byte boundaries of the address space,
+/// starting from the base address. A fetch packet is decoded as such when it
+/// ends with a compact instruction header. Otherwise, and for words outside of
+/// complete fetch packets, every word is decoded as a 32-bit instruction,
+/// relative to the PCE1 address of the fetch packet containing it.
+/// Bytes remaining after the last complete word are not decoded.
+pub struct Disassembler<R> {
+    reader: R,
+    address: u32,
+    endianness: Endianness,
+    window: [u8; PACKET_SIZE],
+    /// Number of bytes of the window already read.
+    window_len: usize,
+    pending: VecDeque<(u32, Box<dyn C6000Instruction>)>,
+    /// p-bit of the last instruction decoded, making the next one parallel.
+    previous_p_bit: bool,
+    finished: bool,
+}
+
+impl<'a> Disassembler<Cursor<&'a [u8]>> {
+    /// Creates a disassembler reading from a byte slice, with the first byte
+    /// at the base address.
+    pub fn from_slice(data: &'a [u8], base_address: u32) -> Self {
+        Self::new(Cursor::new(data), base_address)
+    }
+}
+
+impl<R: Read> Disassembler<R> {
+    /// Creates a disassembler reading from the current position of the reader,
+    /// with the first byte read at the base address.
+    pub fn new(reader: R, base_address: u32) -> Self {
+        Self {
+            reader,
+            address: base_address,
+            endianness: Endianness::Little,
+            window: [0; PACKET_SIZE],
+            window_len: 0,
+            pending: VecDeque::new(),
+            previous_p_bit: false,
+            finished: false,
+        }
+    }
+
+    /// Sets the byte order of the instructions (little-endian by default).
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// Reads from the reader until the window is full or the end is reached.
+    fn fill_window(&mut self) -> io::Result<()> {
+        while self.window_len < PACKET_SIZE {
+            match self.reader.read(&mut self.window[self.window_len..]) {
+                Ok(0) => break,
+                Ok(count) => self.window_len += count,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the address past an instruction, finishing at the end of the
+    /// address space.
+    fn advance(&mut self, size: u32) {
+        match self.address.checked_add(size) {
+            Some(address) => self.address = address,
+            None => self.finished = true,
+        }
+    }
+
+    /// Decodes the bytes of the window, queueing the resulting instructions.
+    fn decode_window(&mut self) {
+        let packet_result =
+            if self.address.is_multiple_of(PACKET_SIZE as u32) && self.window_len == PACKET_SIZE {
+                read_packet(self.window, self.address, self.endianness)
+            } else {
+                Err(DecodeError::NotFetchPacket {
+                    address: self.address,
+                })
+            };
+        if let Ok(mut packet_instructions) = packet_result {
+            // The first instruction may be parallel to the end of the
+            // previous fetch packet
+            packet_instructions[0].set_parallel(self.previous_p_bit);
+            self.previous_p_bit = packet_instructions[packet_instructions.len() - 2].get_p_bit();
+            for instruction in packet_instructions {
+                let size = instruction.size() as u32;
+                self.pending.push_back((self.address, instruction));
+                self.advance(size);
+            }
+            self.window_len = 0;
+        } else {
+            let opcode_bytes = *self
+                .window
+                .first_chunk::<INSTRUCTION_SIZE>()
+                .expect("Getting first chunk of window");
+            match read_instruction(InstructionInput {
+                opcode: self.endianness.read_u32(opcode_bytes),
+                fphead: None,
+                pce1_address: self.address - self.address % PACKET_SIZE as u32,
+            }) {
+                Ok(mut instruction) => {
+                    instruction.set_parallel(self.previous_p_bit);
+                    self.previous_p_bit = instruction.get_p_bit();
+                    self.pending.push_back((self.address, instruction));
+                }
+                Err(_) => self.previous_p_bit = false,
+            }
+            self.advance(INSTRUCTION_SIZE as u32);
+            self.window.copy_within(INSTRUCTION_SIZE.., 0);
+            self.window_len -= INSTRUCTION_SIZE;
+        }
+    }
+}
+
+impl<R: Read> Iterator for Disassembler<R> {
+    type Item = io::Result<(u32, Box<dyn C6000Instruction>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.finished {
+                return None;
+            }
+            if let Err(e) = self.fill_window() {
+                self.finished = true;
+                return Some(Err(e));
+            }
+            if self.window_len < INSTRUCTION_SIZE {
+                self.finished = true;
+                return None;
+            }
+            self.decode_window();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::register::{Register, RegisterFile, register_set};
+
+    fn decode(opcode: u32) -> Box<dyn C6000Instruction> {
+        read_instruction(InstructionInput {
+            opcode,
+            fphead: None,
+            pce1_address: 0x1000,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn disassembler_decodes_words_until_a_fetch_packet() {
+        // LDW.D2T1 *++B15[1], A4, followed by a fetch packet of NOPs
+        let mut data = 0x023C32E4u32.to_le_bytes().to_vec();
+        for _ in 0..7 {
+            data.extend(0u32.to_le_bytes());
+        }
+        data.extend(0xE0000000u32.to_le_bytes());
+
+        let items: Vec<(u32, Box<dyn C6000Instruction>)> = Disassembler::from_slice(&data, 0x7C)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        let addresses: Vec<u32> = items.iter().map(|(address, _)| *address).collect();
+        assert_eq!(addresses, (0x7C..=0x9C).step_by(4).collect::<Vec<u32>>());
+        assert_eq!(items[0].1.instruction(), "LDW.D2T1");
+        assert_eq!(items[1].1.instruction(), "NOP");
+        assert_eq!(items[8].1.instruction(), ".fphead");
+    }
+
+    #[test]
+    fn disassembler_aligns_fetch_packets_to_addresses() {
+        // B.S1 with a displacement of 1, followed by NOPs and an fphead
+        // which don't form a fetch packet at this address
+        let mut data = 0x00000090u32.to_le_bytes().to_vec();
+        for _ in 0..6 {
+            data.extend(0u32.to_le_bytes());
+        }
+        data.extend(0xE0000000u32.to_le_bytes());
+
+        let items: Vec<(u32, Box<dyn C6000Instruction>)> = Disassembler::from_slice(&data, 0x110)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(items.len(), 8);
+        assert_eq!(items[0].1.operands(), "0x00000104 (PCE1+0x00000004)");
+        assert_eq!(items[7].0, 0x12C);
+        assert_eq!(items[7].1.instruction(), "INVALID INSTRUCTION");
+    }
+
+    #[test]
+    fn p_bits_make_words_parallel_without_fetch_packet() {
+        // MVK.S1 0x1234, A3 with its p-bit set, followed by MVK.S2 0x1234, B3
+        let mut data = 0x01891A29u32.to_le_bytes().to_vec();
+        data.extend(0x01891A2Au32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+
+        let items: Vec<(u32, Box<dyn C6000Instruction>)> = Disassembler::from_slice(&data, 0x1000)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        let parallel: Vec<bool> = items
+            .iter()
+            .map(|(_, instruction)| instruction.is_parallel())
+            .collect();
+        assert_eq!(parallel, vec![false, true, false]);
+    }
+
+    #[test]
+    fn disassembler_stops_at_end_of_address_space() {
+        let data = [0u8; PACKET_SIZE + INSTRUCTION_SIZE];
+        let addresses: Vec<u32> = Disassembler::from_slice(&data, 0xFFFFFFE0)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(
+            addresses,
+            (0xFFFFFFE0..=0xFFFFFFFC).step_by(4).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn big_endian_compact_instructions_are_read_in_address_order() {
+        // NOP and NOP 2 in the first word, followed by 32-bit NOPs and an
+        // fphead with the first word holding compact instructions
+        let mut data = vec![0x0C, 0x6E, 0x2C, 0x6E];
+        for _ in 0..6 {
+            data.extend(0u32.to_be_bytes());
+        }
+        data.extend(0xE0200000u32.to_be_bytes());
+
+        let items: Vec<(u32, Box<dyn C6000Instruction>)> = Disassembler::from_slice(&data, 0)
+            .with_endianness(Endianness::Big)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(items.len(), 9);
+        assert_eq!((items[0].0, items[0].1.operands()), (0, String::new()));
+        assert_eq!((items[1].0, items[1].1.operands()), (2, String::from("2")));
+        assert_eq!(items[8].1.instruction(), ".fphead");
+    }
+
+    #[test]
+    fn preincrement_load_writes_back_base_register() {
+        // LDW.D2T1 *++B15[1], A4
+        let instruction = decode(0x023C32E4);
+        assert_eq!(
+            instruction.reads(),
+            register_set([RegisterFile::from(Register::B(15))])
+        );
+        assert_eq!(
+            instruction.writes(),
+            register_set([Register::A(4), Register::B(15)].map(RegisterFile::from))
+        );
+    }
+
+    #[test]
+    fn callp_writes_return_address() {
+        // CALLP.S2 0x00001014, B3
+        let instruction = decode(0x10000292);
+        assert_eq!(instruction.instruction(), "CALLP.S2");
+        assert_eq!(instruction.reads(), register_set([]));
+        assert_eq!(
+            instruction.writes(),
+            register_set([RegisterFile::from(Register::B(3))])
+        );
+        assert_eq!(instruction.timing().delay_slots, 5);
+        assert_eq!(instruction.timing().nop_cycles, 5);
+    }
+
+    #[test]
+    fn load_results_are_written_after_four_delay_slots() {
+        let timing = decode(0x023C32E4).timing();
+        assert_eq!(timing.delay_slots, 4);
+        assert_eq!(timing.writeback_cycle(), 5);
+        assert_eq!(timing.nop_cycles, 0);
+    }
+}
@@ -1,16 +1,27 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write, stdin, stdout},
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write, stdin, stdout},
+    mem,
+    num::ParseIntError,
+    ops::Range,
     path::PathBuf,
     process::exit,
 };
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 use c6000_disassembler::{
-    COMPACT_INSTRUCTION_SIZE, INSTRUCTION_SIZE, PACKET_SIZE,
-    instruction::{C6000Instruction, ConditionalOperation, InstructionInput},
-    read_instruction, read_packet,
+    Disassembler, Endianness, PACKET_SIZE,
+    cfg::ControlFlowGraph,
+    csv::{CSV_COLUMNS, PacketIndices, format_row},
+    dot, flow, format_instruction,
+    functions::{Function, call_graph, find_functions},
+    input::{Image, Section, boot_table, coff, elf, ihex, srec},
+    instruction::{C6000Instruction, mnemonic::Mnemonic},
+    json::format_json,
+    packet::{ExecutePacket, execute_packets},
+    symbols::{SymbolTable, Symbolizer},
 };
 
 #[derive(Parser)]
@@ -27,9 +38,213 @@ struct Args {
 
     /// Memory offset to apply to addresses.
     ///
-    /// Note that this affects the packet fetching process.
-    #[arg(short = 'O', long, default_value_t = 0)]
+    /// Fetch packets are aligned to 32-byte boundaries of the
+    /// offset addresses, so this affects the packet fetching process.
+    #[arg(short = 'O', long, default_value = "0", value_parser = parse_number)]
     offset: u32,
+
+    /// Position in the input file to start reading from.
+    ///
+    /// The bytes before it are skipped, so the byte at this position
+    /// is the first byte of the input.
+    #[arg(long, value_name = "POSITION", default_value = "0", value_parser = parse_number)]
+    file_offset: u32,
+
+    /// Address to start disassembling at, including the offset.
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_number)]
+    start: Option<u32>,
+
+    /// Address to stop disassembling at (excluded), including the offset.
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_number, conflicts_with = "length")]
+    end: Option<u32>,
+
+    /// Number of bytes to disassemble from the start address.
+    #[arg(long, value_parser = parse_number, requires = "start")]
+    length: Option<u32>,
+
+    /// Byte order of the instructions.
+    ///
+    /// If unspecified, the byte order declared by the input
+    /// file is used, or little-endian for raw binaries.
+    #[arg(short, long, value_enum)]
+    endian: Option<Endian>,
+
+    /// File path of a symbol map to use in addition to the
+    /// symbols of the input file.
+    ///
+    /// Every line holds a hexadecimal address followed by a name.
+    #[arg(short, long, value_name = "SYMBOLS_PATH")]
+    symbols: Option<PathBuf>,
+
+    /// Don't generate ``L_XXXXXXXX`` labels for the branch targets
+    /// inside the disassembled range.
+    #[arg(long)]
+    no_labels: bool,
+
+    /// Only disassemble the code reachable from the entry points by
+    /// following branches (recursive descent), listing the rest as data.
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Entry point of the recursive descent, instead of the entry point of
+    /// the input file. Can be given multiple times.
+    #[arg(long = "entry", value_name = "ADDRESS", value_parser = parse_number, requires = "recursive")]
+    entry_points: Vec<u32>,
+
+    /// Print the control-flow graph, made of the basic blocks and
+    /// their successors, instead of the listing.
+    #[arg(long)]
+    cfg: bool,
+
+    /// Print the functions found in the control-flow graph, along with
+    /// their size and the calls between them, instead of the listing.
+    #[arg(long, conflicts_with = "cfg")]
+    functions: bool,
+
+    /// Format of the output.
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Format of the input file.
+    #[arg(short = 'I', long, value_enum, default_value_t = InputFormat::Auto)]
+    input_format: InputFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    /// Detect the format from the contents of the file
+    Auto,
+    /// Raw binary loaded at the offset
+    Raw,
+    /// TI C6000 ELF object file or executable
+    Elf,
+    /// TI C6000 COFF1 or COFF2 object file or executable
+    Coff,
+    /// Intel HEX file
+    Ihex,
+    /// Motorola S-record file
+    Srec,
+    /// TI C6000 boot table, in the byte order of ``--endian``
+    BootTable,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Assembly listing
+    Text,
+    /// Graphviz digraph of the control-flow graph of every function
+    Dot,
+    /// JSON array with an object per instruction
+    Json,
+    /// JSON Lines, with an object per instruction on every line
+    Jsonl,
+    /// Comma-separated values, with a row per instruction
+    Csv,
+    /// Tab-separated values, with a row per instruction
+    Tsv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Endian {
+    Little,
+    Big,
+}
+
+impl From<Endian> for Endianness {
+    fn from(endian: Endian) -> Self {
+        match endian {
+            Endian::Little => Endianness::Little,
+            Endian::Big => Endianness::Big,
+        }
+    }
+}
+
+/// Instructions decoded from the selected part of a section.
+struct Listing<'a> {
+    section: &'a Section,
+    /// Indices of the execute packets of the section, in the execute
+    /// packets of every section.
+    packets: Range<usize>,
+    /// Compact instruction headers, which aren't part of execute packets.
+    headers: Vec<(u32, Box<dyn C6000Instruction>)>,
+}
+
+impl Listing<'_> {
+    /// Returns the instructions of the section, in address order.
+    fn instructions<'a>(
+        &'a self,
+        packets: &'a [ExecutePacket],
+    ) -> Vec<&'a (u32, Box<dyn C6000Instruction>)> {
+        let section_packets = &packets[self.packets.clone()];
+        let mut instructions: Vec<_> = section_packets
+            .iter()
+            .flat_map(|packet| &packet.instructions)
+            .chain(&self.headers)
+            .collect();
+        instructions.sort_by_key(|(address, _)| *address);
+        instructions
+    }
+}
+
+/// Decodes the instructions of the data, exiting on errors.
+fn decode(
+    data: &[u8],
+    address: u32,
+    endianness: Endianness,
+) -> Vec<(u32, Box<dyn C6000Instruction>)> {
+    Disassembler::from_slice(data, address)
+        .with_endianness(endianness)
+        .collect::<io::Result<_>>()
+        .unwrap_or_else(|e| {
+            eprintln!("Error reading from file: {e}");
+            exit(-1);
+        })
+}
+
+/// Parses a decimal number, or a hexadecimal number with a ``0x`` prefix.
+fn parse_number(text: &str) -> Result<u32, ParseIntError> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => text.parse(),
+    }
+}
+
+impl Args {
+    /// Range of addresses to disassemble.
+    fn address_range(&self) -> Range<u64> {
+        let start = self.start.unwrap_or(0) as u64;
+        let end = match (self.end, self.length) {
+            (Some(end), _) => end as u64,
+            (None, Some(length)) => start + length as u64,
+            (None, None) => 1 << 32,
+        };
+        start..end
+    }
+}
+
+/// Returns the address and bytes of the fetch packets of the data overlapping
+/// the address range, if any.
+///
+/// Whole fetch packets are kept so their compact instructions are decoded.
+fn select_fetch_packets<'a>(
+    data: &'a [u8],
+    address: u32,
+    range: &Range<u64>,
+) -> Option<(u32, &'a [u8])> {
+    let data_start = address as u64;
+    let data_end = data_start + data.len() as u64;
+    let start = range.start.max(data_start);
+    let end = range.end.min(data_end);
+    if start >= end {
+        return None;
+    }
+    let packet_size = PACKET_SIZE as u64;
+    let start = (start - start % packet_size).max(data_start);
+    let end = end.next_multiple_of(packet_size).min(data_end);
+    Some((
+        start as u32,
+        &data[(start - data_start) as usize..(end - data_start) as usize],
+    ))
 }
 
 fn handle_output_file(args: &Args) -> Option<BufWriter<File>> {
@@ -63,54 +278,181 @@ fn handle_output_file(args: &Args) -> Option<BufWriter<File>> {
 }
 
 fn print_instruction(
-    instruction: Box<dyn C6000Instruction>,
-    address: &mut u32,
+    instruction: &dyn C6000Instruction,
+    address: u32,
+    operands: String,
     output: &mut dyn Write,
 ) {
+    let line = format_instruction(instruction, address, operands) + "\n";
+    output
+        .write_all(line.as_bytes())
+        .expect("Unable to write to output");
+}
+
+fn print_data(instruction: &dyn C6000Instruction, address: u32, output: &mut dyn Write) {
+    let opcode = instruction.opcode();
+    let (directive, value) = if instruction.is_compact() {
+        (".half", format!("0x{opcode:04X}"))
+    } else {
+        (".word", format!("0x{opcode:08X}"))
+    };
     let line = format!(
         "0x{address:08X}: {:<12}{:<4}{:<6} {:<12} {}\n",
-        format!("{:X}", instruction.opcode()),
-        {
-            if instruction.is_parallel() {
-                String::from("||")
-            } else {
-                String::new()
-            }
-        },
-        {
-            if let Some(operation) = instruction.conditional_operation()
-                && operation != ConditionalOperation::ReservedLow
-                && operation != ConditionalOperation::ReservedHigh
-            {
-                format!("[{:>3}]", operation.to_string())
-            } else {
-                String::new()
-            }
-        },
-        instruction.instruction(),
-        instruction.operands()
+        format!("{opcode:X}"),
+        "",
+        "",
+        directive,
+        value
     );
-
     output
         .write_all(line.as_bytes())
         .expect("Unable to write to output");
+}
 
-    if instruction.is_compact() {
-        *address += COMPACT_INSTRUCTION_SIZE as u32;
-    } else {
-        *address += INSTRUCTION_SIZE as u32;
+fn print_label(name: &str, output: &mut dyn Write) {
+    output
+        .write_all(format!("{name}:\n").as_bytes())
+        .expect("Unable to write to output");
+}
+
+fn print_cfg(
+    graph: &ControlFlowGraph,
+    packets: &[ExecutePacket],
+    symbols: &SymbolTable,
+    output: &mut dyn Write,
+) {
+    let symbolizer = Symbolizer::new(
+        symbols,
+        packets.iter().flat_map(|packet| &packet.instructions),
+    );
+    let name = |address: u32| {
+        symbols
+            .get(address)
+            .map_or_else(|| format!("0x{address:08X}"), String::from)
+    };
+    for (index, block) in graph.blocks.values().enumerate() {
+        let header = format!(
+            "{}; Block {} ({} execute packet{})\n",
+            if index > 0 { "\n" } else { "" },
+            name(block.address),
+            block.packets.len(),
+            if block.packets.len() == 1 { "" } else { "s" }
+        );
+        output
+            .write_all(header.as_bytes())
+            .expect("Unable to write to output");
+        for packet in &packets[block.packets.clone()] {
+            for (address, instruction) in &packet.instructions {
+                let operands = symbolizer.operands(*address, instruction.as_ref());
+                print_instruction(instruction.as_ref(), *address, operands, output);
+            }
+        }
+        for edge in &block.successors {
+            let line = match edge.condition {
+                Some(condition) => format!(
+                    "; -> {} ({} if {condition})\n",
+                    name(edge.target),
+                    edge.kind
+                ),
+                None => format!("; -> {} ({})\n", name(edge.target), edge.kind),
+            };
+            output
+                .write_all(line.as_bytes())
+                .expect("Unable to write to output");
+        }
     }
 }
 
+fn print_functions(
+    functions: &BTreeMap<u32, Function>,
+    symbols: &SymbolTable,
+    output: &mut dyn Write,
+) {
+    let name = |address: u32| {
+        symbols
+            .get(address)
+            .map_or_else(|| format!("0x{address:08X}"), String::from)
+    };
+    let mut lines = vec![String::from("; Functions")];
+    for function in functions.values() {
+        lines.push(format!(
+            "{}: 0x{:08X}-0x{:08X} ({} bytes{})",
+            name(function.address),
+            function.address,
+            function.end,
+            function.size,
+            if function.returns { "" } else { ", no return" }
+        ));
+    }
+    lines.push(String::from("\n; Call graph"));
+    for (caller, callee) in call_graph(functions) {
+        lines.push(format!("{} -> {}", name(caller), name(callee)));
+    }
+    lines.push(String::new());
+    output
+        .write_all(lines.join("\n").as_bytes())
+        .expect("Unable to write to output");
+}
+
+fn print_section_header(section: &Section, separate: bool, output: &mut dyn Write) {
+    let line = format!(
+        "{}; {} (0x{:08X}, {} bytes)\n",
+        if separate { "\n" } else { "" },
+        if section.name.is_empty() {
+            String::from("Segment")
+        } else {
+            format!("Section {}", section.name)
+        },
+        section.address,
+        section.data.len()
+    );
+    output
+        .write_all(line.as_bytes())
+        .expect("Unable to write to output");
+}
+
 fn main() {
     let args = Args::parse();
 
-    let file_result = File::open(&args.file);
-    let Ok(file) = file_result else {
+    let file_result = fs::read(&args.file);
+    let Ok(mut data) = file_result else {
         let _ = file_result.inspect_err(|e| eprintln!("Couldn't open file: {e}"));
         exit(-1);
     };
-    let mut reader = BufReader::new(file);
+    let file_offset = args.file_offset as usize;
+    if file_offset > data.len() {
+        eprintln!("File offset is past the end of the file");
+        exit(-1);
+    }
+    data.drain(..file_offset);
+    let endian = args.endian.map(Endianness::from);
+    let image_result = match args.input_format {
+        InputFormat::Auto if elf::is_elf(&data) => elf::parse(&data),
+        InputFormat::Auto if coff::is_coff(&data) => coff::parse(&data),
+        InputFormat::Auto if ihex::is_ihex(&data) => ihex::parse(&data),
+        InputFormat::Auto if srec::is_srec(&data) => srec::parse(&data),
+        InputFormat::Elf => elf::parse(&data),
+        InputFormat::Coff => coff::parse(&data),
+        InputFormat::Ihex => ihex::parse(&data),
+        InputFormat::Srec => srec::parse(&data),
+        InputFormat::BootTable => boot_table::parse(&data, endian.unwrap_or_default()),
+        InputFormat::Auto | InputFormat::Raw => Ok(Image::from_raw(data, 0)),
+    };
+    let Ok(mut image) = image_result else {
+        let _ = image_result.inspect_err(|e| eprintln!("Couldn't parse file: {e}"));
+        exit(-1);
+    };
+    let mut symbols = mem::take(&mut image.symbols);
+    if let Some(path) = &args.symbols {
+        let map_result = fs::read_to_string(path).and_then(|text| SymbolTable::parse_map(&text));
+        let Ok(map) = map_result else {
+            let _ = map_result.inspect_err(|e| eprintln!("Couldn't read symbols: {e}"));
+            exit(-1);
+        };
+        symbols.extend(map);
+    }
+    let endianness = endian.or(image.endianness).unwrap_or_default();
+
     let mut output_file = handle_output_file(&args);
     let output: &mut dyn Write = {
         if let Some(file) = &mut output_file {
@@ -120,43 +462,222 @@ fn main() {
         }
     };
 
-    let mut address = args.offset;
-    loop {
-        let mut buf = [0u8; PACKET_SIZE];
-        let reader_pos = reader
-            .stream_position()
-            .expect("Reading stream position of reader");
-        if let Err(e) = reader.read_exact(&mut buf) {
-            if e.kind() != ErrorKind::UnexpectedEof {
-                eprintln!("Error reading from file: {e}");
+    if let Some(entry_point) = image.entry_point
+        && args.format == OutputFormat::Text
+    {
+        output
+            .write_all(format!("; Entry point 0x{entry_point:08X}\n").as_bytes())
+            .expect("Unable to write to output");
+    }
+    let range = args.address_range();
+    let mut packets: Vec<ExecutePacket> = Vec::new();
+    let listings: Vec<Listing> = image
+        .executable_sections()
+        .filter_map(|section| {
+            let address = section.address.wrapping_add(args.offset);
+            let (address, data) = select_fetch_packets(&section.data, address, &range)?;
+            let (headers, instructions): (Vec<_>, Vec<_>) = decode(data, address, endianness)
+                .into_iter()
+                .partition(|(_, instruction)| instruction.mnemonic() == Mnemonic::FPHEAD);
+            let start = packets.len();
+            packets.extend(execute_packets(instructions));
+            Some(Listing {
+                section,
+                packets: start..packets.len(),
+                headers,
+            })
+        })
+        .collect();
+
+    let graph_output = args.cfg || args.functions || args.format == OutputFormat::Dot;
+    let records_only = !matches!(args.format, OutputFormat::Text | OutputFormat::Dot);
+    if graph_output && records_only {
+        eprintln!("JSON and CSV output are only available for the listing");
+        exit(-1);
+    }
+    // Addresses of the instructions reachable from the entry points
+    let code: Option<BTreeSet<u32>> = args.recursive.then(|| {
+        let entry_points = if args.entry_points.is_empty() {
+            let Some(entry_point) = image.entry_point else {
+                eprintln!("No entry point to start the recursive descent from");
                 exit(-1);
+            };
+            vec![entry_point.wrapping_add(args.offset)]
+        } else {
+            args.entry_points.clone()
+        };
+        flow::reachable(&packets, &entry_points)
+    });
+    let is_code = |address: u32, instruction: &dyn C6000Instruction| {
+        let Some(code) = &code else {
+            return true;
+        };
+        if instruction.mnemonic() == Mnemonic::FPHEAD {
+            // Headers are code when their fetch packet holds code
+            let packet_address = address - address % PACKET_SIZE as u32;
+            return code.range(packet_address..address).next().is_some();
+        }
+        code.contains(&address)
+    };
+
+    if !args.no_labels {
+        symbols.insert_branch_labels(listings.iter().flat_map(|listing| {
+            listing
+                .instructions(&packets)
+                .into_iter()
+                .filter(|(address, instruction)| {
+                    range.contains(&(*address as u64)) && is_code(*address, instruction.as_ref())
+                })
+        }));
+    }
+
+    if graph_output {
+        let packets: Vec<ExecutePacket> = packets
+            .into_iter()
+            .filter(|packet| {
+                range.contains(&(packet.address as u64))
+                    && code
+                        .as_ref()
+                        .is_none_or(|code| code.contains(&packet.address))
+            })
+            .collect();
+        let graph = ControlFlowGraph::new(&packets);
+        match args.format {
+            OutputFormat::Dot => output
+                .write_all(dot::digraphs(&graph, &packets, &symbols).as_bytes())
+                .expect("Unable to write to output"),
+            _ if args.functions => {
+                print_functions(&find_functions(&graph, &packets), &symbols, output)
             }
-            break;
+            _ => print_cfg(&graph, &packets, &symbols, output),
+        }
+        output.flush().expect("Unable to flush");
+        return;
+    }
+
+    // Record formats describe instructions only, leaving out labels and the
+    // words listed as data
+    let separator = match args.format {
+        OutputFormat::Tsv => '\t',
+        _ => ',',
+    };
+    let mut records = Vec::new();
+    let mut packet_indices = PacketIndices::default();
+    for (index, listing) in listings.iter().enumerate() {
+        if !records_only && (!listing.section.name.is_empty() || listings.len() > 1) {
+            print_section_header(listing.section, index > 0, output);
         }
-        if let Ok(packet_instructions) = read_packet(buf, address) {
-            for instruction in packet_instructions {
-                print_instruction(instruction, &mut address, output);
+        let instructions = listing.instructions(&packets);
+        let symbolizer = Symbolizer::new(&symbols, instructions.iter().copied());
+        for (address, instruction) in instructions {
+            if !range.contains(&(*address as u64)) {
+                continue;
             }
-        } else {
-            reader
-                .seek(SeekFrom::Start(reader_pos + INSTRUCTION_SIZE as u64))
-                .expect("Reader seek to");
-            let opcode_bytes = buf
-                .first_chunk::<INSTRUCTION_SIZE>()
-                .expect("Getting first chunk of buffer")
-                .clone();
-            let opcode = u32::from_le_bytes(opcode_bytes);
-            if let Ok(instruction) = read_instruction(InstructionInput {
-                opcode,
-                fphead: None,
-                pce1_address: address - address % (8 * INSTRUCTION_SIZE as u32),
-            }) {
-                print_instruction(instruction, &mut address, output);
-            } else {
-                address += INSTRUCTION_SIZE as u32;
+            let label = symbols.get(*address);
+            let code = is_code(*address, instruction.as_ref());
+            if records_only {
+                if !code {
+                    continue;
+                }
+                let indices = packet_indices.next(*address, instruction.as_ref());
+                records.push(match args.format {
+                    OutputFormat::Csv | OutputFormat::Tsv => format_row(
+                        instruction.as_ref(),
+                        *address,
+                        indices,
+                        &symbolizer,
+                        separator,
+                    ),
+                    _ => format_json(instruction.as_ref(), *address, label, &symbolizer),
+                });
                 continue;
             }
+            if let Some(name) = label {
+                print_label(name, output);
+            }
+            if !code {
+                print_data(instruction.as_ref(), *address, output);
+                continue;
+            }
+            let operands = symbolizer.operands(*address, instruction.as_ref());
+            print_instruction(instruction.as_ref(), *address, operands, output);
         }
     }
+    let records = match args.format {
+        OutputFormat::Json if records.is_empty() => String::from("[]\n"),
+        OutputFormat::Json => format!("[\n  {}\n]\n", records.join(",\n  ")),
+        OutputFormat::Csv | OutputFormat::Tsv => [CSV_COLUMNS.join(&separator.to_string())]
+            .into_iter()
+            .chain(records)
+            .map(|record| record + "\n")
+            .collect(),
+        _ => records.into_iter().map(|record| record + "\n").collect(),
+    };
+    output
+        .write_all(records.as_bytes())
+        .expect("Unable to write to output");
     output.flush().expect("Unable to flush");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the arguments following the file name.
+    fn args(arguments: &[&str]) -> Args {
+        Args::parse_from(["c6000-disassembler", "file.bin"].iter().chain(arguments))
+    }
+
+    #[test]
+    fn address_range_defaults_to_address_space() {
+        assert_eq!(args(&[]).address_range(), 0..1 << 32);
+        assert_eq!(args(&["--start", "0x100"]).address_range(), 0x100..1 << 32);
+        assert_eq!(args(&["--end", "0x100"]).address_range(), 0..0x100);
+        assert_eq!(
+            args(&["--start", "0x100", "--length", "0x20"]).address_range(),
+            0x100..0x120
+        );
+    }
+
+    #[test]
+    fn whole_fetch_packets_are_selected() {
+        let data: Vec<u8> = (0..0x80).collect();
+        // Start alone, on a fetch packet boundary
+        assert_eq!(
+            select_fetch_packets(&data, 0x1000, &(0x1020..1 << 32)),
+            Some((0x1020, &data[0x20..]))
+        );
+        // End alone, inside a fetch packet
+        assert_eq!(
+            select_fetch_packets(&data, 0x1000, &(0..0x1024)),
+            Some((0x1000, &data[..0x40]))
+        );
+        // Neither on a fetch packet boundary
+        assert_eq!(
+            select_fetch_packets(&data, 0x1000, &(0x1024..0x1044)),
+            Some((0x1020, &data[0x20..0x60]))
+        );
+        // Data not starting on a fetch packet boundary
+        assert_eq!(
+            select_fetch_packets(&data, 0x1010, &(0x1024..0x1028)),
+            Some((0x1020, &data[0x10..0x30]))
+        );
+        // Outside of the data
+        assert_eq!(select_fetch_packets(&data, 0x1000, &(0..0x1000)), None);
+        assert_eq!(
+            select_fetch_packets(&data, 0x1000, &(0x1080..1 << 32)),
+            None
+        );
+    }
+
+    #[test]
+    fn file_offset_moves_data_not_addresses() {
+        let args = args(&["--file-offset", "0x20", "-O", "0x1000", "--start", "0x1020"]);
+        let data: Vec<u8> = (0..0x60).collect();
+        let data = &data[args.file_offset as usize..];
+        assert_eq!(
+            select_fetch_packets(data, args.offset, &args.address_range()),
+            Some((0x1020, &data[0x20..]))
+        );
+    }
+}
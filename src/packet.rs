@@ -0,0 +1,166 @@
+use crate::instruction::{
+    C6000Instruction, fphead::CompactInstructionHeader, mnemonic::InstructionClass,
+};
+
+/// Group of instructions executed in parallel in the same cycle.
+pub struct ExecutePacket {
+    /// Address of the first instruction.
+    pub address: u32,
+    /// Number of bytes from the first instruction to the end of the last one.
+    ///
+    /// This includes the compact instruction header when the execute packet
+    /// spans two fetch packets.
+    pub size: u32,
+    /// Instructions of the execute packet, along with their addresses.
+    pub instructions: Vec<(u32, Box<dyn C6000Instruction>)>,
+}
+
+impl ExecutePacket {
+    fn new(address: u32, instruction: Box<dyn C6000Instruction>) -> Self {
+        Self {
+            address,
+            size: instruction.size() as u32,
+            instructions: vec![(address, instruction)],
+        }
+    }
+
+    fn push(&mut self, address: u32, instruction: Box<dyn C6000Instruction>) {
+        self.size = address.wrapping_sub(self.address) + instruction.size() as u32;
+        self.instructions.push((address, instruction));
+    }
+
+    /// Returns the address following the last instruction.
+    pub fn end(&self) -> u32 {
+        self.address.wrapping_add(self.size)
+    }
+
+    /// Returns the number of cycles the execute packet takes, including
+    /// the ``NOP`` cycles of multi-cycle ``NOP``, ``BNOP``, ``CALLP`` and ``ADDKPC``.
+    pub fn cycles(&self) -> u32 {
+        self.instructions
+            .iter()
+            .map(|(_, instruction)| {
+                let nop_cycles = instruction.timing().nop_cycles as u32;
+                match instruction.class() {
+                    InstructionClass::Nop => nop_cycles.max(1),
+                    // The NOP cycles of other instructions follow the cycle
+                    // they're issued in
+                    _ => 1 + nop_cycles,
+                }
+            })
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Determines if the last instruction is followed by a parallel instruction.
+    fn continues(&self) -> bool {
+        self.instructions
+            .last()
+            .is_some_and(|(_, instruction)| instruction.get_p_bit())
+    }
+}
+
+/// Iterator grouping decoded instructions into execute packets, using the
+/// p-bit of every instruction.
+///
+/// Compact instruction headers are not part of any execute packet and are
+/// skipped, so execute packets may span fetch packet boundaries.
+pub struct ExecutePackets<I> {
+    instructions: I,
+    pending: Option<ExecutePacket>,
+}
+
+impl<I: Iterator<Item = (u32, Box<dyn C6000Instruction>)>> Iterator for ExecutePackets<I> {
+    type Item = ExecutePacket;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (address, instruction) in self.instructions.by_ref() {
+            if instruction.as_any().is::<CompactInstructionHeader>() {
+                continue;
+            }
+            match &mut self.pending {
+                Some(packet) if packet.continues() => packet.push(address, instruction),
+                _ => {
+                    let packet = self
+                        .pending
+                        .replace(ExecutePacket::new(address, instruction));
+                    if packet.is_some() {
+                        return packet;
+                    }
+                }
+            }
+        }
+        self.pending.take()
+    }
+}
+
+/// Groups `(address, instruction)` pairs into execute packets.
+pub fn execute_packets<I>(instructions: I) -> ExecutePackets<I::IntoIter>
+where
+    I: IntoIterator<Item = (u32, Box<dyn C6000Instruction>)>,
+{
+    ExecutePackets {
+        instructions: instructions.into_iter(),
+        pending: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Endianness, read_packet};
+
+    /// Pairs the instructions returned by [crate::read_packet] with their addresses.
+    fn with_addresses(
+        instructions: Vec<Box<dyn C6000Instruction>>,
+        address: u32,
+    ) -> impl Iterator<Item = (u32, Box<dyn C6000Instruction>)> {
+        let mut next_address = address;
+        instructions.into_iter().map(move |instruction| {
+            let address = next_address;
+            next_address = next_address.wrapping_add(instruction.size() as u32);
+            (address, instruction)
+        })
+    }
+
+    fn packet(words: [u32; 8]) -> [u8; crate::PACKET_SIZE] {
+        let mut bytes = [0u8; crate::PACKET_SIZE];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn execute_packets_span_fetch_packets() {
+        // Fetch packets of NOPs with an fphead (but no compact instructions),
+        // where the p-bits chain the last two NOPs of the first fetch packet
+        // to the first NOP of the second one
+        let header = 0xE0000000;
+        let nop = 0x00000000;
+        let parallel_nop = 0x00000001;
+        let first = packet([nop, nop, nop, nop, nop, parallel_nop, parallel_nop, header]);
+        let second = packet([nop, nop, nop, nop, nop, nop, nop, header]);
+
+        let instructions = with_addresses(
+            read_packet(first, 0x100, Endianness::Little).unwrap(),
+            0x100,
+        )
+        .chain(with_addresses(
+            read_packet(second, 0x120, Endianness::Little).unwrap(),
+            0x120,
+        ));
+        let packets: Vec<ExecutePacket> = execute_packets(instructions).collect();
+
+        assert_eq!(packets.len(), 12);
+        let spanning = &packets[5];
+        assert_eq!(spanning.address, 0x114);
+        assert_eq!(spanning.size, 0x10);
+        let addresses: Vec<u32> = spanning
+            .instructions
+            .iter()
+            .map(|(address, _)| *address)
+            .collect();
+        assert_eq!(addresses, vec![0x114, 0x118, 0x120]);
+    }
+}
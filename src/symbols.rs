@@ -0,0 +1,231 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{Error, ErrorKind, Result},
+};
+
+use crate::instruction::{
+    C6000Instruction, branching::BranchInstruction, mnemonic::Mnemonic, operand::Operand,
+    register::Register,
+};
+
+/// Names of the addresses of a program, such as functions and labels.
+#[derive(Clone, Default, Debug)]
+pub struct SymbolTable {
+    symbols: BTreeMap<u32, String>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a symbol map, made of lines holding a hexadecimal address
+    /// (with an optional ``0x`` prefix) followed by a name.
+    ///
+    /// Empty lines and lines starting with ``#`` or ``;`` are ignored.
+    pub fn parse_map(text: &str) -> Result<Self> {
+        let mut table = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let invalid_line = || {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid symbol on line {}: {line}", index + 1),
+                )
+            };
+            let (address, name) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(invalid_line)?;
+            let address = address.trim_start_matches("0x").trim_start_matches("0X");
+            let address = u32::from_str_radix(address, 16).map_err(|_| invalid_line())?;
+            table.insert(address, name.trim());
+        }
+        Ok(table)
+    }
+
+    /// Adds a symbol, unless the address already has a name.
+    pub fn insert(&mut self, address: u32, name: &str) {
+        self.symbols
+            .entry(address)
+            .or_insert_with(|| String::from(name));
+    }
+
+    /// Adds the symbols of another table, keeping the existing names.
+    pub fn extend(&mut self, other: SymbolTable) {
+        for (address, name) in other.symbols {
+            self.symbols.entry(address).or_insert(name);
+        }
+    }
+
+    /// Adds a ``L_XXXXXXXX`` label for every branch target that is the
+    /// address of one of the instructions and has no name yet.
+    pub fn insert_branch_labels<'a, I>(&mut self, instructions: I)
+    where
+        I: IntoIterator<Item = &'a (u32, Box<dyn C6000Instruction>)> + Clone,
+    {
+        let addresses: HashSet<u32> = instructions
+            .clone()
+            .into_iter()
+            .map(|(address, _)| *address)
+            .collect();
+        for (_, instruction) in instructions {
+            if let Some(target) = BranchInstruction::target_of(instruction.as_ref())
+                && addresses.contains(&target)
+            {
+                self.insert(target, &format!("L_{target:08X}"));
+            }
+        }
+    }
+
+    pub fn get(&self, address: u32) -> Option<&str> {
+        self.symbols.get(&address).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.symbols
+            .iter()
+            .map(|(address, name)| (*address, name.as_str()))
+    }
+}
+
+/// Renders operands with the addresses of symbols replaced by their names.
+///
+/// Constants built by a ``MVK``/``MVKH`` pair writing the same register are
+/// resolved, so both instructions of the pair are rendered with the symbol.
+pub struct Symbolizer<'a> {
+    symbols: &'a SymbolTable,
+    /// Constant built by the ``MVK``/``MVKH`` pair, by instruction address.
+    constants: HashMap<u32, u32>,
+}
+
+impl<'a> Symbolizer<'a> {
+    /// Creates a symbolizer for the instructions, in address order.
+    pub fn new<'b, I>(symbols: &'a SymbolTable, instructions: I) -> Self
+    where
+        I: IntoIterator<Item = &'b (u32, Box<dyn C6000Instruction>)>,
+    {
+        let mut constants = HashMap::new();
+        let mut low_halves: HashMap<Register, (u32, u32)> = HashMap::new();
+        for (address, instruction) in instructions {
+            let operands = instruction.operand_list();
+            if let [Operand::Immediate(constant), Operand::Register(register)] = operands[..] {
+                match instruction.mnemonic() {
+                    Mnemonic::MVK => {
+                        low_halves.insert(register, (*address, constant & 0xFFFF));
+                        continue;
+                    }
+                    Mnemonic::MVKH => {
+                        if let Some((low_address, low)) = low_halves.remove(&register) {
+                            let value = constant << 16 | low;
+                            constants.insert(low_address, value);
+                            constants.insert(*address, value);
+                        }
+                        continue;
+                    }
+                    _ => (),
+                }
+            }
+            for register in instruction.writes() {
+                if let Some(register) = Operand::from(register).register() {
+                    low_halves.remove(&register);
+                }
+            }
+        }
+        Self { symbols, constants }
+    }
+
+    /// Renders an operand of the instruction at the address.
+    pub fn operand(&self, address: u32, operand: &Operand) -> String {
+        let symbol = match operand {
+            Operand::BranchTarget { .. } => operand
+                .target_address()
+                .and_then(|target| self.symbols.get(target)),
+            Operand::Immediate(_) => self
+                .constants
+                .get(&address)
+                .and_then(|value| self.symbols.get(*value)),
+            _ => None,
+        };
+        match symbol {
+            Some(name) => String::from(name),
+            None => operand.to_string(),
+        }
+    }
+
+    /// Renders the operands of the instruction at the address,
+    /// along with its comment.
+    pub fn operands(&self, address: u32, instruction: &dyn C6000Instruction) -> String {
+        let operands = instruction
+            .operand_list()
+            .iter()
+            .map(|operand| self.operand(address, operand))
+            .collect::<Vec<String>>()
+            .join(", ");
+        match instruction.comment() {
+            Some(comment) => format!("{operands} {comment}"),
+            None => operands,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Disassembler;
+
+    #[test]
+    fn symbol_map_is_parsed() {
+        let table =
+            SymbolTable::parse_map("# comment\n0x80000000 _c_int00\n\n80001000  _main\n").unwrap();
+        assert_eq!(table.get(0x80000000), Some("_c_int00"));
+        assert_eq!(table.get(0x80001000), Some("_main"));
+        assert!(SymbolTable::parse_map("_main").is_err());
+    }
+
+    #[test]
+    fn constant_pairs_are_symbolized() {
+        // MVK.S1 0x1234, A3, NOP, MVKH.S1 0x8000, A3
+        let mut data = Vec::new();
+        for opcode in [0x01891A28u32, 0, 0x01C00068] {
+            data.extend(opcode.to_le_bytes());
+        }
+        let instructions: Vec<(u32, Box<dyn C6000Instruction>)> =
+            Disassembler::from_slice(&data, 0)
+                .map(|item| item.unwrap())
+                .collect();
+        let mut symbols = SymbolTable::new();
+        symbols.insert(0x80001234, "_table");
+
+        let symbolizer = Symbolizer::new(&symbols, &instructions);
+        let rendered: Vec<String> = instructions
+            .iter()
+            .map(|(address, instruction)| symbolizer.operands(*address, instruction.as_ref()))
+            .collect();
+        assert_eq!(rendered, vec!["_table, A3", "", "_table, A3"]);
+    }
+
+    #[test]
+    fn branch_targets_are_labeled() {
+        // B.S1 0x1000 from PCE1 0x1000, NOP, B.S1 0x2000
+        let mut data = Vec::new();
+        for opcode in [0x00000010u32, 0, 0x00020010] {
+            data.extend(opcode.to_le_bytes());
+        }
+        let instructions: Vec<(u32, Box<dyn C6000Instruction>)> =
+            Disassembler::from_slice(&data, 0x1000)
+                .map(|item| item.unwrap())
+                .collect();
+        let mut symbols = SymbolTable::new();
+        symbols.insert_branch_labels(&instructions);
+
+        let labels: Vec<(u32, &str)> = symbols.iter().collect();
+        assert_eq!(labels, vec![(0x1000, "L_00001000")]);
+    }
+}